@@ -10,8 +10,8 @@ fn wrap(data: &mut Vec<u8>) {
     native_model::wrapper::native_model_encode(data, 1, 1);
 }
 
-fn unwrap(data: &mut Vec<u8>) {
-    native_model::wrapper::Wrapper::deserialize(&data[..]).unwrap();
+fn unwrap(data: &[u8]) {
+    native_model::wrapper::Wrapper::deserialize(data).unwrap();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -30,9 +30,9 @@ fn criterion_benchmark(c: &mut Criterion) {
 
         // decode
         let data = Data(vec![1; nb_bytes]);
-        let mut encode_body = native_model::encode(&data).unwrap();
+        let encode_body = native_model::encode(&data).unwrap();
         group.bench_function(BenchmarkId::new("decode", nb_bytes), |b| {
-            b.iter(|| unwrap(&mut encode_body))
+            b.iter(|| unwrap(&encode_body))
         });
     }
 }
@@ -0,0 +1,103 @@
+use crate::ModelAttributes;
+use proc_macro2::{Group, Spacing, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{DeriveInput, Lifetime};
+
+/// Checks at compile time that the models the model is upgraded from or
+/// downgraded to have the same id and a lower version.
+///
+/// Models with type or const parameters aren't checked, a constant can't
+/// depend on them. Functions given to `upgrade` and `downgrade` aren't
+/// checked either, their models are only known once they are called.
+pub(crate) fn generate_chain_check(attrs: &ModelAttributes, ast: &DeriveInput) -> TokenStream {
+    let krate = &attrs.krate;
+    if ast.generics.type_params().next().is_some() || ast.generics.const_params().next().is_some() {
+        return quote! {};
+    }
+
+    let struct_name = &ast.ident;
+    let lifetimes = ast.generics.lifetimes().map(|_| quote! { 'static });
+    let model = quote! { #struct_name<#(#lifetimes),*> };
+
+    let previous = attrs
+        .from
+        .iter()
+        .chain(attrs.try_from.iter().map(|(try_from, _)| try_from))
+        .chain(&attrs.upgrade_from)
+        .chain(
+            attrs
+                .try_upgrade_from
+                .iter()
+                .map(|(upgrade_from, _)| upgrade_from),
+        )
+        .chain(
+            attrs
+                .upgrade_with
+                .iter()
+                .map(|(upgrade_with, _)| upgrade_with),
+        )
+        .chain(&attrs.downgrade_to)
+        .chain(
+            attrs
+                .try_downgrade_to
+                .iter()
+                .map(|(downgrade_to, _)| downgrade_to),
+        );
+
+    let checks = previous.map(|previous| {
+        let name = previous.to_token_stream().to_string().replace(' ', "");
+        let same_id = format!("`{}` and `{}` have different ids", struct_name, name);
+        let lower_version = format!(
+            "`{}` must have a lower version than `{}`",
+            name, struct_name
+        );
+        let previous = with_static_lifetimes(previous.to_token_stream());
+        quote! {
+            assert!(
+                <#previous as #krate::Model>::ID
+                    == <#model as #krate::Model>::ID,
+                #same_id
+            );
+            assert!(
+                <#previous as #krate::Model>::VERSION
+                    < <#model as #krate::Model>::VERSION,
+                #lower_version
+            );
+        }
+    });
+
+    quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    }
+}
+
+/// Replaces the lifetimes of `tokens` by `'static`, the lifetimes of the
+/// model aren't in scope of a constant.
+fn with_static_lifetimes(tokens: TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct)
+                if punct.as_char() == '\'' && punct.spacing() == Spacing::Joint =>
+            {
+                if let Some(TokenTree::Ident(_)) = tokens.peek() {
+                    tokens.next();
+                    Lifetime::new("'static", punct.span()).to_tokens(&mut result);
+                } else {
+                    result.extend([TokenTree::Punct(punct)]);
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    Group::new(group.delimiter(), with_static_lifetimes(group.stream()));
+                new_group.set_span(group.span());
+                result.extend([TokenTree::Group(new_group)]);
+            }
+            token => result.extend([token]),
+        }
+    }
+    result
+}
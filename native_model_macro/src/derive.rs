@@ -0,0 +1,340 @@
+use crate::{generate_model_impls, ModelAttributes};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    Data, DeriveInput, Error, Expr, ExprLit, Field, Fields, Ident, Lit, LitInt, LitStr, Path,
+    Result,
+};
+
+/// Attributes of the fields, listed in the errors for unknown ones.
+const FIELD_ATTRIBUTES: &[&str] = &["added_in", "removed_in", "renamed_from", "default"];
+
+#[derive(Default)]
+struct FieldAttributes {
+    // version adding the field
+    added_in: Option<LitInt>,
+    // version removing the field, after the version of the model
+    removed_in: Option<LitInt>,
+    // name of the field in the previous version
+    renamed_from: Option<Ident>,
+    // value of the field when the other version doesn't have it
+    default: Option<Expr>,
+}
+
+impl FieldAttributes {
+    fn parse(field: &Field) -> Result<Self> {
+        let mut attrs = FieldAttributes::default();
+        for attr in &field.attrs {
+            if !attr.path().is_ident("native_model") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("added_in") {
+                    attrs.added_in = Some(parse_version(&meta)?);
+                } else if meta.path.is_ident("removed_in") {
+                    attrs.removed_in = Some(parse_version(&meta)?);
+                } else if meta.path.is_ident("renamed_from") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    attrs.renamed_from = Some(name.parse()?);
+                } else if meta.path.is_ident("default") {
+                    attrs.default = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error(format!(
+                        "unknown field attribute, expected one of {}",
+                        FIELD_ATTRIBUTES
+                            .iter()
+                            .map(|attribute| format!("`{}`", attribute))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+
+    /// Checks the attributes against `version`, the version of the model.
+    fn validate(&self, version: Option<u32>) -> Result<()> {
+        for (name, lit) in [
+            ("added_in", &self.added_in),
+            ("removed_in", &self.removed_in),
+        ] {
+            let lit = match lit {
+                Some(lit) => lit,
+                None => continue,
+            };
+            let version = version.ok_or_else(|| {
+                Error::new(
+                    lit.span(),
+                    format!("`{}` requires a literal `version` of the model", name),
+                )
+            })?;
+            let value = lit.base10_parse::<u32>()?;
+            if name == "added_in" && value > version {
+                return Err(Error::new(
+                    lit.span(),
+                    format!(
+                        "`added_in` can't be after the version of the model, {}",
+                        version
+                    ),
+                ));
+            }
+            if name == "removed_in" && value <= version {
+                return Err(Error::new(
+                    lit.span(),
+                    format!(
+                        "`removed_in` must be after the version of the model, {}",
+                        version
+                    ),
+                ));
+            }
+        }
+        if let (Some(_), Some(removed_in)) = (&self.added_in, &self.removed_in) {
+            return Err(Error::new(
+                removed_in.span(),
+                "`removed_in` can't be combined with `added_in`, give `removed_in` on the last version having the field",
+            ));
+        }
+        if let (true, Some(renamed_from)) = (self.is_added(version), &self.renamed_from) {
+            return Err(Error::new(
+                renamed_from.span(),
+                "`renamed_from` can't be given to a field added in this version",
+            ));
+        }
+        if let (None, None, Some(default)) = (&self.added_in, &self.removed_in, &self.default) {
+            return Err(Error::new_spanned(
+                default,
+                "`default` requires `added_in` or `removed_in`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the field is added in `version`, so missing from the previous
+    /// version.
+    fn is_added(&self, version: Option<u32>) -> bool {
+        match (&self.added_in, version) {
+            (Some(added_in), Some(version)) => added_in.base10_parse::<u32>().ok() == Some(version),
+            _ => false,
+        }
+    }
+
+    fn default_value(&self) -> TokenStream {
+        match &self.default {
+            Some(default) => quote! { #default },
+            None => quote! { ::std::default::Default::default() },
+        }
+    }
+}
+
+fn parse_version(meta: &syn::meta::ParseNestedMeta) -> Result<LitInt> {
+    let lit: LitInt = meta.value()?.parse()?;
+    lit.base10_parse::<u32>()?;
+    Ok(lit)
+}
+
+/// The literal version of the model, field attributes compare their
+/// versions to it.
+fn literal_version(attrs: &ModelAttributes) -> Option<u32> {
+    match &attrs.version {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        _ => None,
+    }
+}
+
+pub(crate) fn generate_native_model_derive(ast: &DeriveInput) -> Result<TokenStream> {
+    let mut attrs = ModelAttributes::default();
+    for attr in &ast.attrs {
+        if attr.path().is_ident("native_model") {
+            attr.parse_nested_meta(|meta| attrs.parse(meta))?;
+        }
+    }
+    attrs.validate()?;
+    attrs.resolve_defaults();
+    let version = literal_version(&attrs);
+
+    let fields = match &ast.data {
+        Data::Struct(data) if !matches!(data.fields, Fields::Unnamed(_)) => Some(&data.fields),
+        _ => None,
+    };
+    let field_attrs = match fields {
+        Some(fields) => fields
+            .iter()
+            .map(|field| {
+                let field_attrs = FieldAttributes::parse(field)?;
+                field_attrs.validate(version)?;
+                Ok((field, field_attrs))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => vec![],
+    };
+
+    // The conversions are generated for non-generic structs with named fields.
+    let convertible = fields.is_some() && ast.generics.params.is_empty();
+    if !convertible {
+        check_no_field_attributes(ast)?;
+    }
+
+    let impls = generate_model_impls(&attrs, ast);
+    let next_fields = if convertible {
+        generate_next_fields(&attrs, ast, &field_attrs)
+    } else {
+        quote! {}
+    };
+    let conversions = match &attrs.from {
+        Some(from) if convertible => {
+            generate_conversions(&attrs, ast, from, &field_attrs, version)
+        }
+        Some(from) => {
+            return Err(Error::new_spanned(
+                from,
+                "`from` of `#[derive(NativeModel)]` generates the conversions, which requires a non-generic struct with named fields, use `#[native_model]` with your own conversions instead",
+            ))
+        }
+        None => quote! {},
+    };
+
+    Ok(quote! {
+        #impls
+        #next_fields
+        #conversions
+    })
+}
+
+/// Field attributes require a non-generic struct with named fields, they
+/// can't be given to other models.
+fn check_no_field_attributes(ast: &DeriveInput) -> Result<()> {
+    let fields: Vec<&Field> = match &ast.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .collect(),
+        Data::Union(data) => data.fields.named.iter().collect(),
+    };
+    for field in fields {
+        if let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("native_model"))
+        {
+            return Err(Error::new_spanned(
+                attr,
+                "field attributes of `NativeModel` require a non-generic struct with named fields",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Implements `DerivedNativeModel`: the fields of the model kept in the next
+/// version, the ones without `removed_in`, and their conversion into the
+/// model which fills the removed fields with their default.
+fn generate_next_fields(
+    attrs: &ModelAttributes,
+    ast: &DeriveInput,
+    fields: &[(&Field, FieldAttributes)],
+) -> TokenStream {
+    let krate = &attrs.krate;
+    let model = &ast.ident;
+    let kept = fields
+        .iter()
+        .filter(|(_, attrs)| attrs.removed_in.is_none())
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+    let kept_vis = kept.iter().map(|field| &field.vis);
+    let kept_ty = kept.iter().map(|field| &field.ty);
+    let kept = kept.iter().map(|field| &field.ident).collect::<Vec<_>>();
+    let removed = fields
+        .iter()
+        .filter(|(_, attrs)| attrs.removed_in.is_some())
+        .map(|(field, _)| &field.ident);
+    let removed_default = fields
+        .iter()
+        .filter(|(_, attrs)| attrs.removed_in.is_some())
+        .map(|(_, attrs)| attrs.default_value());
+
+    // The fields are declared in an anonymous const, only the next version
+    // names them through the trait.
+    quote! {
+        const _: () = {
+            pub struct NativeModelNext {
+                #(#kept_vis #kept: #kept_ty,)*
+            }
+
+            impl #krate::conversion::DerivedNativeModel for #model {
+                type Next = NativeModelNext;
+
+                fn from_next(next: NativeModelNext) -> Self {
+                    #model {
+                        #(#kept: next.#kept,)*
+                        #(#removed: #removed_default,)*
+                    }
+                }
+            }
+        };
+    }
+}
+
+/// Generates the `From` conversions between the model and the previous
+/// version given to `from`, which must derive `NativeModel` as well.
+fn generate_conversions(
+    attrs: &ModelAttributes,
+    ast: &DeriveInput,
+    from: &Path,
+    fields: &[(&Field, FieldAttributes)],
+    version: Option<u32>,
+) -> TokenStream {
+    let krate = &attrs.krate;
+    let model = &ast.ident;
+    // Spanned on `from`, a previous version without the derive fails there.
+    let derived = quote_spanned! {from.span()=>
+        <#from as #krate::conversion::DerivedNativeModel>
+    };
+    let next = quote_spanned! {from.span()=> Next };
+
+    let added = fields
+        .iter()
+        .filter(|(_, attrs)| attrs.is_added(version))
+        .collect::<Vec<_>>();
+    let added_name = added.iter().map(|(field, _)| &field.ident);
+    let added_default = added.iter().map(|(_, attrs)| attrs.default_value());
+    let previous = fields
+        .iter()
+        .filter(|(_, attrs)| !attrs.is_added(version))
+        .collect::<Vec<_>>();
+    let name = previous
+        .iter()
+        .map(|(field, _)| field.ident.as_ref())
+        .collect::<Vec<_>>();
+    let previous_name = previous
+        .iter()
+        .map(|(field, attrs)| attrs.renamed_from.as_ref().or(field.ident.as_ref()))
+        .collect::<Vec<_>>();
+
+    quote! {
+        impl ::std::convert::From<#from> for #model {
+            fn from(previous: #from) -> Self {
+                #model {
+                    #(#name: previous.#previous_name,)*
+                    #(#added_name: #added_default,)*
+                }
+            }
+        }
+
+        impl ::std::convert::From<#model> for #from {
+            fn from(model: #model) -> Self {
+                // Struct expressions don't take qualified paths.
+                type #next = #derived::Next;
+                #derived::from_next(#next {
+                    #(#previous_name: model.#name,)*
+                })
+            }
+        }
+    }
+}
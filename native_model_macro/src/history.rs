@@ -0,0 +1,305 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Error, Expr, Ident, Path, Result, Token, Type, Visibility};
+
+/// Input of the `history!` macro:
+/// `[#[attr]] [vis type Name;] [crate = path;] id = u32; Type, Type => Type`,
+/// each type optionally preceded by the name of its variant, `Variant = Type`.
+pub(crate) struct History {
+    // outer attributes, forwarded to the versions enum
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    // base name of the generated items, `Latest` and `Versions` are appended
+    name: Option<Ident>,
+    krate: Path,
+    id: Expr,
+    // older versions, in increasing order
+    previous: Vec<Version>,
+    latest: Version,
+}
+
+/// A version of the history, `[Variant =] Type`.
+struct Version {
+    // name of the variant, the last identifier of the path of `ty` by default
+    variant: Option<Ident>,
+    ty: Type,
+}
+
+impl Parse for Version {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let variant = if input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![=>]) {
+            let variant = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Some(variant)
+        } else {
+            None
+        };
+        Ok(Version {
+            variant,
+            ty: input.parse()?,
+        })
+    }
+}
+
+impl Version {
+    fn variant(&self) -> Result<&Ident> {
+        match &self.variant {
+            Some(variant) => Ok(variant),
+            None => type_ident(&self.ty),
+        }
+    }
+}
+
+impl Parse for History {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        let name = if input.peek(Token![type]) {
+            input.parse::<Token![type]>()?;
+            let name = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Some(name)
+        } else if !matches!(vis, Visibility::Inherited) {
+            return Err(input.error("expected `type`, i.e. `pub type Foo;`"));
+        } else {
+            None
+        };
+
+        let mut krate = None;
+        let mut id = None;
+        // `=` also peeks the first character of `=>`. Named versions follow
+        // `id`, only `crate` is a key after it.
+        while input.peek(Token![crate])
+            || id.is_none()
+                && input.peek(Ident)
+                && input.peek2(Token![=])
+                && !input.peek2(Token![=>])
+        {
+            if input.peek(Token![crate]) {
+                let token = input.parse::<Token![crate]>()?;
+                if krate.is_some() {
+                    return Err(Error::new(token.span, "duplicate `crate`"));
+                }
+                input.parse::<Token![=]>()?;
+                krate = Some(input.parse()?);
+            } else {
+                let key: Ident = input.parse()?;
+                if key != "id" {
+                    return Err(Error::new(
+                        key.span(),
+                        format!("unknown key `{}`, expected `id` or `crate`", key),
+                    ));
+                }
+                if id.is_some() {
+                    return Err(Error::new(key.span(), "duplicate `id`"));
+                }
+                input.parse::<Token![=]>()?;
+                id = Some(input.parse()?);
+            }
+            input.parse::<Token![;]>()?;
+        }
+        let id = id.ok_or_else(|| input.error("missing `id`, i.e. `id = 1;`"))?;
+
+        let previous = Punctuated::<Version, Token![,]>::parse_separated_nonempty(input)?;
+        let mut versions = previous.into_iter().collect::<Vec<_>>();
+        let latest = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            input.parse()?
+        } else {
+            versions
+                .pop()
+                .ok_or_else(|| input.error("expected the versions of the model"))?
+        };
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the latest version"));
+        }
+
+        Ok(History {
+            attrs,
+            vis,
+            name,
+            krate: krate.unwrap_or_else(|| syn::parse_quote!(native_model)),
+            id,
+            previous: versions,
+            latest,
+        })
+    }
+}
+
+/// The last identifier of the path of `ty`, naming its variant by default.
+fn type_ident(ty: &Type) -> Result<&Ident> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) if segment.arguments.is_empty() => Ok(&segment.ident),
+            _ => Err(Error::new_spanned(ty, "expected a model without generics")),
+        },
+        _ => Err(Error::new_spanned(ty, "expected the path of a model")),
+    }
+}
+
+/// The name of the model without its version, i.e. `Foo` for `Foo3` or
+/// `FooV3`.
+fn base_name(latest: &Ident) -> Option<Ident> {
+    let name = latest.to_string();
+    let without_version = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let without_version = if without_version.len() < name.len() {
+        without_version
+            .strip_suffix('V')
+            .filter(|name| !name.is_empty())
+            .unwrap_or(without_version)
+    } else {
+        without_version
+    };
+    if without_version.is_empty() {
+        None
+    } else {
+        Some(Ident::new(without_version, latest.span()))
+    }
+}
+
+pub(crate) fn generate_history(history: &History) -> Result<TokenStream> {
+    let History {
+        attrs,
+        vis,
+        krate,
+        id,
+        latest,
+        ..
+    } = history;
+    let variants = history
+        .previous
+        .iter()
+        .chain(std::iter::once(latest))
+        .map(Version::variant)
+        .collect::<Result<Vec<_>>>()?;
+    let versions = history
+        .previous
+        .iter()
+        .chain(std::iter::once(latest))
+        .map(|version| &version.ty)
+        .collect::<Vec<_>>();
+    for (i, variant) in variants.iter().enumerate() {
+        if let Some(duplicate) = variants[..i].iter().find(|other| *other == variant) {
+            let ty = versions[i];
+            let mut error = Error::new(
+                variant.span(),
+                format!(
+                    "`{}` is given twice, the versions name the variants: name them, i.e. `V{} = {}`",
+                    variant,
+                    i + 1,
+                    quote!(#ty).to_string().replace(' ', "")
+                ),
+            );
+            error.combine(Error::new(duplicate.span(), "first given here"));
+            return Err(error);
+        }
+    }
+    let name = match &history.name {
+        Some(name) => name.clone(),
+        None => type_ident(&latest.ty)
+            .ok()
+            .and_then(base_name)
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    &latest.ty,
+                    "the name of the model is missing, i.e. `type Foo;`",
+                )
+            })?,
+    };
+    let latest_alias = format_ident!("{}Latest", name);
+    let versions_enum = format_ident!("{}Versions", name);
+
+    let checks = versions.iter().enumerate().map(|(i, ty)| {
+        let same_id = format!("`{}` doesn't have the id of the history", variants[i]);
+        let next = versions.get(i + 1).map(|next| {
+            let lower_version = format!(
+                "`{}` must have a lower version than `{}`",
+                variants[i],
+                variants[i + 1]
+            );
+            quote! {
+                assert!(
+                    <#ty as #krate::Model>::VERSION < <#next as #krate::Model>::VERSION,
+                    #lower_version
+                );
+            }
+        });
+        quote! {
+            assert!(<#ty as #krate::Model>::ID == #id, #same_id);
+            #next
+        }
+    });
+
+    let latest_ty = &latest.ty;
+    let latest_doc = format!(
+        "The latest version of the model, `{}`.",
+        quote!(#latest_ty).to_string().replace(' ', "")
+    );
+    let versions_doc = format!(
+        "The versions of the model with id `{}`, decoded without upgrade by `decode_any`.",
+        quote!(#id).to_string().replace(' ', "")
+    );
+
+    Ok(quote! {
+        const _: () = {
+            #(#checks)*
+        };
+
+        #[doc = #latest_doc]
+        #vis type #latest_alias = #latest_ty;
+
+        #[doc = #versions_doc]
+        #(#attrs)*
+        #vis enum #versions_enum {
+            #(#variants(#versions),)*
+        }
+
+        impl #versions_enum {
+            /// Decodes `data` into the variant of the version it was encoded
+            /// with, without upgrading it.
+            pub fn decode_any(data: &[u8]) -> #krate::Result<Self> {
+                let header = #krate::peek(data)?;
+                if header.id != #id {
+                    return Err(#krate::Error::WrongTypeId {
+                        expected: #id,
+                        actual: header.id,
+                    });
+                }
+                #(
+                    if header.version == <#versions as #krate::Model>::VERSION {
+                        let (model, _) = #krate::decode_slice::<#versions>(data)?;
+                        return Ok(#versions_enum::#variants(model));
+                    }
+                )*
+                Err(#krate::Error::UnknownModelVersion {
+                    id: header.id,
+                    version: header.version,
+                })
+            }
+
+            /// The version of the model held by the variant.
+            pub fn version(&self) -> u32 {
+                match self {
+                    #(#versions_enum::#variants(_) => <#versions as #krate::Model>::VERSION,)*
+                }
+            }
+        }
+
+        impl #krate::ModelVersions for #versions_enum {
+            const ID: u32 = #id;
+
+            fn native_model_decode_as_stored(data: &[u8]) -> #krate::Result<Self> {
+                Self::decode_any(data)
+            }
+
+            fn native_model_version(&self) -> u32 {
+                self.version()
+            }
+        }
+    })
+}
@@ -1,68 +1,355 @@
 extern crate proc_macro;
 
+mod chain_check;
+mod derive;
+mod history;
 mod method;
+mod model_enum;
 
+use crate::chain_check::generate_chain_check;
+use crate::derive::generate_native_model_derive;
+use crate::history::{generate_history, History};
 use crate::method::{
-    generate_native_model_decode_body, generate_native_model_decode_upgrade_body,
-    generate_native_model_encode_body, generate_native_model_encode_downgrade_body,
-    generate_native_model_id, generate_native_model_version,
+    generate_native_model_decode_body, generate_native_model_decode_borrowed_body,
+    generate_native_model_decode_borrowing_model, generate_native_model_decode_from_reader,
+    generate_native_model_decode_upgrade_body,
+    generate_native_model_decode_upgrade_body_with_context,
+    generate_native_model_decode_upgrade_body_with_report,
+    generate_native_model_decode_upgrade_borrowed_body, generate_native_model_encode_body,
+    generate_native_model_encode_downgrade_body, generate_native_model_id,
+    generate_native_model_info, generate_native_model_upgrade_cost, generate_native_model_version,
 };
+use crate::model_enum::generate_native_model_enum;
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
 use syn::meta::ParseNestedMeta;
-use syn::parse::{Parse, Result};
+use syn::parse::{Parse, ParseStream, Parser, Result};
 use syn::punctuated::Punctuated;
 use syn::token;
-use syn::{parse_macro_input, DeriveInput, LitInt, Path, Token};
+use syn::{
+    parse_macro_input, parse_quote, DeriveInput, Expr, ExprLit, Generics, Ident, Lit, Path, Token,
+};
+
+/// Attributes of the macro, listed in the suggestions for unknown ones.
+const ATTRIBUTES: &[&str] = &[
+    "id",
+    "version",
+    "with",
+    "from",
+    "try_from",
+    "upgrade_from",
+    "try_upgrade_from",
+    "downgrade_to",
+    "try_downgrade_to",
+    "upgrade",
+    "downgrade",
+    "upgrade_with",
+    "crate",
+];
 
 // Inspiration: https://docs.rs/syn/2.0.29/syn/meta/fn.parser.html#example-1
 pub(crate) struct ModelAttributes {
-    pub(crate) id: Option<LitInt>,
-    pub(crate) version: Option<LitInt>,
+    // u32 literal or constant
+    pub(crate) id: Expr,
+    // u32 literal or constant
+    pub(crate) version: Expr,
     // type
-    pub(crate) with: Option<Path>,
+    pub(crate) with: Path,
     // type
     pub(crate) from: Option<Path>,
     // (type, try_from::Error type)
     pub(crate) try_from: Option<(Path, Path)>,
+    // types converted with `From` directly into this version
+    pub(crate) upgrade_from: Vec<Path>,
+    // (type, try_from::Error type) converted with `TryFrom` directly into this version
+    pub(crate) try_upgrade_from: Vec<(Path, Path)>,
+    // type converted with `From` from this version
+    pub(crate) downgrade_to: Option<Path>,
+    // (type, try_from::Error type) converted with `TryFrom` from this version
+    pub(crate) try_downgrade_to: Option<(Path, Path)>,
+    // fn(previous type) -> Result<Self, E>
+    pub(crate) upgrade: Option<Path>,
+    // fn(Self) -> Result<previous type, E>
+    pub(crate) downgrade: Option<Path>,
+    // (type, context type) converted with `UpgradeWith`
+    pub(crate) upgrade_with: Option<(Path, Path)>,
+    // path of the native_model crate in the generated code
+    pub(crate) krate: Path,
+    // attributes given, to report the missing, duplicate and conflicting ones
+    given: Vec<Ident>,
 }
 
 impl Default for ModelAttributes {
     fn default() -> Self {
         ModelAttributes {
-            id: None,
-            version: None,
-            with: Some(syn::parse_str::<Path>("native_model::bincode_1_3::Bincode").unwrap()),
+            // Placeholders, `id` and `version` are required.
+            id: parse_quote!(0),
+            version: parse_quote!(0),
+            // Placeholder, the default depends on `crate`.
+            with: parse_quote!(native_model::bincode_1_3::Bincode),
             from: None,
             try_from: None,
+            upgrade_from: Vec::new(),
+            try_upgrade_from: Vec::new(),
+            downgrade_to: None,
+            try_downgrade_to: None,
+            upgrade: None,
+            downgrade: None,
+            upgrade_with: None,
+            krate: parse_quote!(native_model),
+            given: Vec::new(),
         }
     }
 }
 
 impl ModelAttributes {
     fn parse(&mut self, meta: ParseNestedMeta) -> Result<()> {
-        if meta.path.is_ident("id") {
-            self.id = Some(meta.value()?.parse()?);
-        } else if meta.path.is_ident("version") {
-            self.version = Some(meta.value()?.parse()?);
-        } else if meta.path.is_ident("with") {
-            self.with = Some(meta.value()?.parse()?);
-        } else if meta.path.is_ident("from") {
+        let name = match meta.path.get_ident() {
+            Some(name) if ATTRIBUTES.contains(&name.to_string().as_str()) => name.clone(),
+            _ => return Err(unknown_attribute(&meta.path)),
+        };
+        if let Some(given) = self.given.iter().find(|given| **given == name) {
+            let mut error = syn::Error::new(name.span(), format!("duplicate `{}` attribute", name));
+            error.combine(syn::Error::new(given.span(), "first given here"));
+            return Err(error);
+        }
+
+        if name == "id" {
+            self.id = parse_u32(&meta, "id")?;
+        } else if name == "version" {
+            self.version = parse_u32(&meta, "version")?;
+        } else if name == "with" {
+            self.with = meta.value()?.parse()?;
+        } else if name == "from" {
             self.from = Some(meta.value()?.parse()?);
-        } else if meta.path.is_ident("try_from") {
-            let tuple_try_from: TupleTryFrom = meta.value()?.parse()?;
-            let mut fields = tuple_try_from.fields.into_iter();
-            self.try_from.replace((
-                fields.next().unwrap().clone(),
-                fields.next().unwrap().clone(),
+        } else if name == "try_from" {
+            self.try_from = Some(parse_pair(&meta, "(type, error)")?);
+        } else if name == "upgrade_from" {
+            let value = meta.value()?;
+            if value.peek(token::Bracket) {
+                let content;
+                syn::bracketed!(content in value);
+                self.upgrade_from = content
+                    .parse_terminated(Path::parse, Token![,])?
+                    .into_iter()
+                    .collect();
+            } else {
+                self.upgrade_from = vec![value.parse()?];
+            }
+        } else if name == "try_upgrade_from" {
+            self.try_upgrade_from = parse_pairs(&meta, "(type, error)")?;
+        } else if name == "downgrade_to" {
+            self.downgrade_to = Some(meta.value()?.parse()?);
+        } else if name == "try_downgrade_to" {
+            let mut pairs = parse_pairs(&meta, "(type, error)")?.into_iter();
+            self.try_downgrade_to = pairs.next();
+            if let Some((second, _)) = pairs.next() {
+                return Err(syn::Error::new_spanned(
+                    second,
+                    "`try_downgrade_to` takes a single `(type, error)`, the previous version the model downgrades to",
+                ));
+            }
+        } else if name == "upgrade" {
+            self.upgrade = Some(meta.value()?.parse()?);
+        } else if name == "downgrade" {
+            self.downgrade = Some(meta.value()?.parse()?);
+        } else if name == "upgrade_with" {
+            self.upgrade_with = Some(parse_pair(&meta, "(type, context)")?);
+        } else if name == "crate" {
+            self.krate = meta.value()?.parse()?;
+        }
+        self.given.push(name);
+        Ok(())
+    }
+
+    /// Whether the model is encoded with the default codec of native_model,
+    /// `bincode_1_3::Bincode`.
+    fn has_default_codec(&self) -> bool {
+        !self.given.iter().any(|given| given == "with")
+    }
+
+    /// Defaults of the attributes depending on other attributes.
+    fn resolve_defaults(&mut self) {
+        if self.has_default_codec() {
+            let krate = &self.krate;
+            self.with = parse_quote!(#krate::bincode_1_3::Bincode);
+        }
+    }
+
+    /// Checks that the required attributes are given and that the given ones
+    /// can be combined.
+    fn validate(&self) -> Result<()> {
+        for required in ["id", "version"] {
+            if !self.given.iter().any(|given| given == required) {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!(
+                        "missing `{}` attribute, i.e. `#[native_model(id = 1, version = 1)]`",
+                        required
+                    ),
+                ));
+            }
+        }
+
+        self.conflicts(&["from", "try_from", "upgrade", "upgrade_with"], "")?;
+        self.conflicts(
+            &[
+                "from",
+                "try_from",
+                "downgrade_to",
+                "try_downgrade_to",
+                "downgrade",
+            ],
+            ", `from` and `try_from` already downgrade",
+        )
+    }
+
+    /// Fails if more than one of `attributes` is given.
+    fn conflicts(&self, attributes: &[&str], note: &str) -> Result<()> {
+        let mut given = self
+            .given
+            .iter()
+            .filter(|given| attributes.iter().any(|attribute| *given == attribute));
+        if let (Some(first), Some(second)) = (given.next(), given.next()) {
+            let list = attributes
+                .iter()
+                .map(|attribute| format!("`{}`", attribute))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(syn::Error::new(
+                second.span(),
+                format!(
+                    "`{}` can't be combined with `{}`, only one of {} can be used{}",
+                    second, first, list, note
+                ),
             ));
-        } else {
-            panic!("Unknown attribute: {}", meta.path.get_ident().unwrap());
         }
         Ok(())
     }
 }
 
+fn unknown_attribute(path: &Path) -> syn::Error {
+    let name = path
+        .get_ident()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| quote!(#path).to_string().replace(' ', ""));
+    let suggestion = ATTRIBUTES
+        .iter()
+        .map(|attribute| (edit_distance(&name, attribute), attribute))
+        .filter(|(distance, attribute)| *distance <= attribute.len() / 3 + 1)
+        .min_by_key(|(distance, _)| *distance);
+    let message = match suggestion {
+        Some((_, attribute)) => format!(
+            "unknown attribute `{}`, did you mean `{}`?",
+            name, attribute
+        ),
+        None => format!(
+            "unknown attribute `{}`, expected one of {}",
+            name,
+            ATTRIBUTES
+                .iter()
+                .map(|attribute| format!("`{}`", attribute))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    syn::Error::new_spanned(path, message)
+}
+
+/// Levenshtein distance, counting a swap of two adjacent letters as a
+/// single edit (`verison` is one edit away from `version`).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// Parses a `u32` literal or the path of a constant.
+fn parse_u32(meta: &ParseNestedMeta, name: &str) -> Result<Expr> {
+    let expr: Expr = meta.value()?.parse()?;
+    match &expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => {
+            int.base10_parse::<u32>()?;
+            if !matches!(int.suffix(), "" | "u32") {
+                return Err(syn::Error::new(
+                    int.span(),
+                    format!("`{}` must be a `u32`, found `{}`", name, int.suffix()),
+                ));
+            }
+        }
+        Expr::Path(_) => {}
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &expr,
+                format!("`{}` must be a `u32` literal or constant", name),
+            ))
+        }
+    }
+    Ok(expr)
+}
+
+/// Parses `(type, type)`, `expected` describes the pair in errors.
+fn parse_pair(meta: &ParseNestedMeta, expected: &str) -> Result<(Path, Path)> {
+    parse_pair_value(meta.value()?, expected)
+}
+
+/// Parses a pair or a list of pairs, i.e. `[(type, error), ...]`.
+fn parse_pairs(meta: &ParseNestedMeta, expected: &str) -> Result<Vec<(Path, Path)>> {
+    let value = meta.value()?;
+    if !value.peek(token::Bracket) {
+        return Ok(vec![parse_pair_value(value, expected)?]);
+    }
+    let content;
+    syn::bracketed!(content in value);
+    let mut pairs = vec![];
+    while !content.is_empty() {
+        pairs.push(parse_pair_value(&content, expected)?);
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<Token![,]>()?;
+    }
+    Ok(pairs)
+}
+
+fn parse_pair_value(value: ParseStream, expected: &str) -> Result<(Path, Path)> {
+    if !value.peek(token::Paren) {
+        return Err(value.error(format!("expected `{}`", expected)));
+    }
+    let tuple_try_from: TupleTryFrom = value.parse()?;
+    let span = tuple_try_from._parent_token.span.join();
+    let mut fields = tuple_try_from.fields.into_iter();
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some(first), Some(second), None) => Ok((first, second)),
+        _ => Err(syn::Error::new(
+            span,
+            format!("expected `{}`, a pair of types", expected),
+        )),
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct TupleTryFrom {
     pub(crate) _parent_token: token::Paren,
@@ -82,14 +369,36 @@ impl Parse for TupleTryFrom {
 /// Macro which add identity and version to your rust type.
 ///
 /// Attributes:
-/// - `id = u32`: The unique identifier of the model.
-/// - `version = u32`: The version of the model.
-/// - `with` = type: Required, the serialization/deserialization library that you use. Must implement `native_model::Encode` and `native_model::Decode`.
+/// - `id = u32`: The unique identifier of the model, a literal or a constant.
+/// - `version = u32`: The version of the model, a literal or a constant.
+/// - `with` = type: Optional, the serialization/deserialization library that you use. Must implement `native_model::Encode` and `native_model::Decode`.
+///   `native_model::bincode_1_3::Bincode` by default, which requires the `bincode_1_3` feature.
 /// - `from = type`: Optional, the previous version of the model.
 ///     - `type`: The previous version of the model that you use for the From implementation.
 /// - `try_from = (type, error)`: Optional, the previous version of the model with error handling.
 ///     - `type`: The previous version of the model that you use for the TryFrom implementation.
 ///     - `error`: The error type that you use for the TryFrom implementation.
+/// - `upgrade_from = type` or `upgrade_from = [type, ...]`: Optional, older versions of the model
+///   converted into this version with a From implementation, without downgrade. When decoding, the
+///   upgrade path with the fewest conversions is used, `from` and `try_from` are preferred when they
+///   are as short.
+/// - `try_upgrade_from = (type, error)` or `try_upgrade_from = [(type, error), ...]`: Optional, same
+///   as `upgrade_from` with a TryFrom implementation.
+/// - `downgrade_to = type`: Optional, the previous version of the model that this version is
+///   converted into with a From implementation when encoding an older version, without upgrade.
+///   Models without `from`, `try_from`, `downgrade_to` or `try_downgrade_to` return
+///   `DowngradeNotSupported`.
+/// - `try_downgrade_to = (type, error)`: Optional, same as `downgrade_to` with a TryFrom implementation.
+/// - `upgrade = path`: Optional, a function `fn(Previous) -> Result<Self, E>` upgrading the previous
+///   version of the model, inferred from its signature, instead of `from` or `try_from`.
+/// - `downgrade = path`: Optional, a function `fn(Self) -> Result<Previous, E>` downgrading the model
+///   to its previous version, instead of `from`, `try_from`, `downgrade_to` or `try_downgrade_to`.
+///     - `E`: The error of the conversion, it must implement `Display` and `Into<anyhow::Error>`.
+/// - `upgrade_with = (type, context)`: Optional, the previous version of the model converted with an
+///   `UpgradeWith<type, context>` implementation, instead of `from`, `try_from` or `upgrade`. Such models
+///   are upgraded by `decode_with_context` only.
+/// - `crate = path`: Optional, the path of the `native_model` crate used by the generated code,
+///   `native_model` by default. For crates re-exporting `native_model`, i.e. `crate = platform::native_model`.
 ///
 /// See examples:
 ///    - [Setup your data model](https://github.com/vincent-herlemont/native_model_private#setup-your-data-model).
@@ -97,31 +406,299 @@ impl Parse for TupleTryFrom {
 #[proc_macro_attribute]
 pub fn native_model(args: TokenStream, input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
-    let struct_name = &ast.ident;
 
     let mut attrs = ModelAttributes::default();
     let model_attributes_parser = syn::meta::parser(|meta| attrs.parse(meta));
-    parse_macro_input!(args with model_attributes_parser);
-
-    let native_model_id_fn = generate_native_model_id(&attrs);
-    let native_model_version_fn = generate_native_model_version(&attrs);
-    let native_model_encode_body_fn = generate_native_model_encode_body(&attrs);
-    let native_model_encode_downgrade_body_fn = generate_native_model_encode_downgrade_body(&attrs);
-    let native_model_decode_body_fn = generate_native_model_decode_body(&attrs);
-    let native_model_decode_upgrade_body_fn = generate_native_model_decode_upgrade_body(&attrs);
+    // The model is kept on error, so its uses don't report errors as well.
+    if let Err(error) = model_attributes_parser
+        .parse(args)
+        .and_then(|()| attrs.validate())
+    {
+        let error = error.into_compile_error();
+        return quote! {
+            #ast
+            #error
+        }
+        .into();
+    }
+    attrs.resolve_defaults();
 
+    let impls = generate_model_impls(&attrs, &ast);
     let gen = quote! {
         #ast
 
-        impl native_model::Model for #struct_name {
+        #impls
+    };
+
+    gen.into()
+}
+
+/// Implementations of `Model` and `BorrowModel` for `ast`, shared by the
+/// attribute and the derive macros.
+fn generate_model_impls(attrs: &ModelAttributes, ast: &DeriveInput) -> TokenStream2 {
+    let struct_name = &ast.ident;
+    let krate = &attrs.krate;
+
+    let native_model_id_fn = generate_native_model_id(attrs);
+    let native_model_version_fn = generate_native_model_version(attrs);
+    let native_model_info_const = generate_native_model_info(attrs, struct_name);
+    let native_model_encode_body_fn = generate_native_model_encode_body(attrs);
+    let native_model_encode_downgrade_body_fn = generate_native_model_encode_downgrade_body(attrs);
+    let native_model_upgrade_cost_fn = generate_native_model_upgrade_cost(attrs);
+    // Models with lifetimes can only be decoded by borrowing from the bytes,
+    // the others get the owned and the streaming implementations.
+    let native_model_decode_fns = if ast.generics.lifetimes().next().is_none() {
+        let native_model_decode_body_fn = generate_native_model_decode_body(attrs);
+        let native_model_decode_upgrade_body_fn = generate_native_model_decode_upgrade_body(attrs);
+        let native_model_decode_upgrade_body_with_report_fn =
+            generate_native_model_decode_upgrade_body_with_report(attrs);
+        let native_model_decode_upgrade_body_with_context_fn =
+            generate_native_model_decode_upgrade_body_with_context(attrs);
+        let native_model_decode_from_reader_fn = generate_native_model_decode_from_reader(attrs);
+        quote! {
+            #native_model_decode_body_fn
+            #native_model_decode_upgrade_body_fn
+            #native_model_decode_upgrade_body_with_report_fn
+            #native_model_decode_upgrade_body_with_context_fn
+            #native_model_decode_from_reader_fn
+        }
+    } else {
+        generate_native_model_decode_borrowing_model(attrs)
+    };
+    let native_model_decode_borrowed_body_fn =
+        generate_native_model_decode_borrowed_body(attrs, &ast.generics);
+    let native_model_decode_upgrade_borrowed_body_fn =
+        generate_native_model_decode_upgrade_borrowed_body(attrs, &ast.generics);
+
+    let chain_check = generate_chain_check(attrs, ast);
+
+    let model_generics = model_generics(&ast.generics, struct_name, attrs);
+    let (impl_generics, ty_generics, where_clause) = model_generics.split_for_impl();
+    let borrow_generics = borrow_model_generics(&ast.generics, struct_name, attrs);
+    let (borrow_impl_generics, _, borrow_where_clause) = borrow_generics.split_for_impl();
+
+    let impls = quote! {
+        impl #impl_generics #krate::Model for #struct_name #ty_generics #where_clause {
             #native_model_id_fn
             #native_model_version_fn
+            #native_model_info_const
+            #native_model_upgrade_cost_fn
             #native_model_encode_body_fn
             #native_model_encode_downgrade_body_fn
-            #native_model_decode_body_fn
-            #native_model_decode_upgrade_body_fn
+            #native_model_decode_fns
+        }
+
+        impl #borrow_impl_generics #krate::BorrowModel<'native_model_de> for #struct_name #ty_generics #borrow_where_clause {
+            #native_model_decode_borrowed_body_fn
+            #native_model_decode_upgrade_borrowed_body_fn
         }
+
+        #chain_check
     };
+    // Without `with`, the implementations only compile if the feature of the
+    // default codec is enabled, which fails with a clear error otherwise.
+    if attrs.has_default_codec() {
+        quote! {
+            #krate::__with_default_codec! {
+                #impls
+            }
+        }
+    } else {
+        impls
+    }
+}
 
-    gen.into()
+/// Derive alternative to the [`native_model`](macro@native_model) attribute,
+/// taking the same attributes in `#[native_model(...)]`, i.e.
+/// `#[derive(NativeModel)] #[native_model(id = 1, version = 2, from = FooV1)]`.
+///
+/// With `from`, the `From` conversions with the previous version are
+/// generated, it must derive `NativeModel` as well: `from` fails to compile
+/// otherwise, not implementing `DerivedNativeModel`. The fields are converted by
+/// name, their `#[native_model(...)]` attributes describe the changes:
+/// - `added_in = u32`: The version adding the field. A field added in the version of the model
+///   isn't in the previous version, it is set to its `default` on upgrade and dropped on downgrade.
+/// - `removed_in = u32`: The version removing the field, given on the last version having the
+///   field. It is set to its `default` when the next version is downgraded.
+/// - `renamed_from = "name"`: The name of the field in the previous version.
+/// - `default = expr`: The value of an added or removed field, `Default::default()` by default.
+///
+/// Only non-generic structs with named fields get the conversions. Use the
+/// attribute to write your own conversions.
+#[proc_macro_derive(NativeModel, attributes(native_model))]
+pub fn native_model_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    generate_native_model_derive(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares the history of a model, the versions of a model id from the
+/// oldest to the latest, i.e. `history! { id = 7; Foo1, Foo2 => Foo3 }`.
+///
+/// Checks at compile time that the versions have the id and increasing
+/// versions, and generates:
+/// - `type FooLatest = Foo3`: The latest version of the model.
+/// - `enum FooVersions { Foo1(Foo1), Foo2(Foo2), Foo3(Foo3) }`: One variant per version, named
+///   after the type of the version or given before it, i.e. `V1 = v1::Foo` for types of the same
+///   name in different modules, with:
+///     - `decode_any(data)`: Decodes the model in the variant of the version it was encoded with,
+///       without upgrading it.
+///     - `version(&self)`: The version of the variant.
+///
+///   It implements `ModelVersions`, decoded by `native_model::decode_as_stored`.
+///
+/// The name of the generated items is the name of the latest version without
+/// its version, or is given with their visibility by `pub type Foo;` before
+/// `id`. `crate = path` sets the path of `native_model` as in the attribute.
+/// Outer attributes written first, i.e. `#[derive(Debug)]`, are forwarded to
+/// the enum.
+#[proc_macro]
+pub fn history(input: TokenStream) -> TokenStream {
+    let history = parse_macro_input!(input as History);
+    generate_history(&history)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive for enums holding one model per variant, i.e.
+/// `enum Message { A(ModelA), B(ModelB) }`.
+///
+/// Generates:
+/// - `native_model_decode(data)`: decodes the model of the variant with the id
+///   of `data`, upgraded to its version, and returns the version it was encoded with.
+/// - `native_model_encode(&self)`: encodes the model of the variant.
+///
+/// Variants sharing a model id are rejected at compile time.
+///
+/// `#[native_model(crate = path)]` sets the path of `native_model` as in the
+/// attribute.
+#[proc_macro_derive(NativeModelEnum, attributes(native_model))]
+pub fn native_model_enum(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    generate_native_model_enum(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generics of the `Model` implementation. Models with type parameters get
+/// the bounds of the codec, which can't be checked for every parameter.
+fn model_generics(generics: &Generics, struct_name: &Ident, attrs: &ModelAttributes) -> Generics {
+    let (krate, with) = (&attrs.krate, &attrs.with);
+    let mut model_generics = generics.clone();
+    if generics.type_params().next().is_some() {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let model = quote! { #struct_name #ty_generics };
+        let predicates = &mut model_generics.make_where_clause().predicates;
+        predicates.push(parse_quote!(#with: #krate::Encode<#model>));
+        predicates.push(parse_quote!(
+            <#with as #krate::Encode<#model>>::Error: #krate::conversion::CodecError
+        ));
+        // Only models without lifetimes are decoded by `Model`.
+        if generics.lifetimes().next().is_none() {
+            predicates.push(parse_quote!(#with: #krate::Decode<#model>));
+            predicates.push(parse_quote!(
+                <#with as #krate::Decode<#model>>::Error: #krate::conversion::CodecError
+            ));
+        }
+    }
+    model_generics
+}
+
+/// Adds the `'native_model_de` lifetime of the decoded bytes to the generics
+/// of the model, it must outlive every lifetime of the model.
+fn borrow_model_generics(
+    generics: &Generics,
+    struct_name: &Ident,
+    attrs: &ModelAttributes,
+) -> Generics {
+    let (krate, with) = (&attrs.krate, &attrs.with);
+    let mut borrow_generics = model_generics(generics, struct_name, attrs);
+    let lifetimes = generics
+        .lifetimes()
+        .map(|param| param.lifetime.clone())
+        .collect::<Vec<_>>();
+    borrow_generics
+        .params
+        .insert(0, parse_quote!('native_model_de: #(#lifetimes)+*));
+    if generics.type_params().next().is_some() && !lifetimes.is_empty() {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let model = quote! { #struct_name #ty_generics };
+        let predicates = &mut borrow_generics.make_where_clause().predicates;
+        predicates.push(parse_quote!(
+            #with: #krate::BorrowDecode<'native_model_de, #model>
+        ));
+        predicates.push(parse_quote!(
+            <#with as #krate::BorrowDecode<'native_model_de, #model>>::Error: #krate::conversion::CodecError
+        ));
+    }
+    borrow_generics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, unknown_attribute, ModelAttributes};
+    use syn::{parse_quote, Attribute, Path};
+
+    fn parse_error(attr: Attribute) -> String {
+        let mut attrs = ModelAttributes::default();
+        attr.parse_nested_meta(|meta| attrs.parse(meta))
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn edit_distance_counts_edits() {
+        assert_eq!(edit_distance("version", "version"), 0);
+        assert_eq!(edit_distance("versio", "version"), 1);
+        assert_eq!(edit_distance("versions", "version"), 1);
+        assert_eq!(edit_distance("varsion", "version"), 1);
+        assert_eq!(edit_distance("", "with"), 4);
+        assert_eq!(edit_distance("upgrade", "downgrade"), 4);
+    }
+
+    #[test]
+    fn edit_distance_counts_swap_as_one_edit() {
+        assert_eq!(edit_distance("verison", "version"), 1);
+        assert_eq!(edit_distance("wiht", "with"), 1);
+        assert_eq!(edit_distance("di", "id"), 1);
+    }
+
+    #[test]
+    fn unknown_attribute_suggests_closest() {
+        let path: Path = parse_quote!(verison);
+        assert_eq!(
+            unknown_attribute(&path).to_string(),
+            "unknown attribute `verison`, did you mean `version`?"
+        );
+        let path: Path = parse_quote!(try_upgrade_form);
+        assert_eq!(
+            unknown_attribute(&path).to_string(),
+            "unknown attribute `try_upgrade_form`, did you mean `try_upgrade_from`?"
+        );
+    }
+
+    #[test]
+    fn unknown_attribute_lists_attributes_without_close_one() {
+        let path: Path = parse_quote!(codec);
+        let message = unknown_attribute(&path).to_string();
+        assert!(message
+            .starts_with("unknown attribute `codec`, expected one of `id`, `version`, `with`"));
+
+        let path: Path = parse_quote!(platform::version);
+        let message = unknown_attribute(&path).to_string();
+        assert!(message.starts_with("unknown attribute `platform::version`, expected one of"));
+    }
+
+    #[test]
+    fn parse_reports_unknown_attribute() {
+        assert_eq!(
+            parse_error(parse_quote!(#[native_model(id = 1, verison = 1)])),
+            "unknown attribute `verison`, did you mean `version`?"
+        );
+        assert_eq!(
+            parse_error(parse_quote!(#[native_model(id = 1, id = 2)])),
+            "duplicate `id` attribute"
+        );
+    }
 }
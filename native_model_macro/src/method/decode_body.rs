@@ -1,18 +1,83 @@
 use crate::ModelAttributes;
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::Generics;
 
 pub(crate) fn generate_native_model_decode_body(attrs: &ModelAttributes) -> TokenStream {
-    let id = attrs.id.clone().expect("`id` is required");
-    let with = attrs.with.clone().expect("`with` is required");
+    let krate = &attrs.krate;
+    let id = &attrs.id;
+    let with = &attrs.with;
+
+    let gen = quote! {
+        fn native_model_decode_body(data: &[u8], id: u32) -> std::result::Result<Self, #krate::DecodeBodyError> {
+            if id != #id {
+                return Err(#krate::DecodeBodyError::MismatchedModelId);
+            }
+
+            use #krate::Decode;
+            #with::decode_slice(data).map_err(|e| #krate::DecodeBodyError::DecodeError {
+                msg: format!("{}", e),
+                source: e.into(),
+            })
+        }
+    };
+
+    gen
+}
+
+/// The `Model` decode methods of the models with lifetimes, they can't be
+/// decoded without borrowing from the bytes and only implement
+/// `BorrowModel`.
+pub(crate) fn generate_native_model_decode_borrowing_model(attrs: &ModelAttributes) -> TokenStream {
+    let krate = &attrs.krate;
+
+    let gen = quote! {
+        fn native_model_decode_body(_data: &[u8], _id: u32) -> std::result::Result<Self, #krate::DecodeBodyError> {
+            Err(#krate::DecodeBodyError::BorrowedModel)
+        }
+
+        fn native_model_decode_upgrade_body(_data: &[u8], _id: u32, _version: u32) -> #krate::Result<Self> {
+            Err(#krate::DecodeBodyError::BorrowedModel.into())
+        }
+
+        fn native_model_decode_upgrade_body_with_report(_data: &[u8], _id: u32, _version: u32, _report: &mut #krate::UpgradeReport) -> #krate::Result<Self> {
+            Err(#krate::DecodeBodyError::BorrowedModel.into())
+        }
+
+        fn native_model_decode_upgrade_body_with_context(_data: &[u8], _id: u32, _version: u32, _context: &dyn std::any::Any) -> #krate::Result<Self> {
+            Err(#krate::DecodeBodyError::BorrowedModel.into())
+        }
+    };
+
+    gen
+}
+
+pub(crate) fn generate_native_model_decode_borrowed_body(
+    attrs: &ModelAttributes,
+    generics: &Generics,
+) -> TokenStream {
+    let krate = &attrs.krate;
+    let id = &attrs.id;
+    let with = &attrs.with;
+
+    // Models with lifetimes borrow from the body, the others are decoded
+    // by their `Model` implementation.
+    if generics.lifetimes().next().is_none() {
+        return quote! {
+            fn native_model_decode_borrowed_body(data: &'native_model_de [u8], id: u32) -> std::result::Result<Self, #krate::DecodeBodyError> {
+                <Self as #krate::Model>::native_model_decode_body(data, id)
+            }
+        };
+    }
+
     let gen = quote! {
-        fn native_model_decode_body(data: Vec<u8>, id: u32) -> std::result::Result<Self, native_model::DecodeBodyError> {
+        fn native_model_decode_borrowed_body(data: &'native_model_de [u8], id: u32) -> std::result::Result<Self, #krate::DecodeBodyError> {
             if id != #id {
-                return Err(native_model::DecodeBodyError::MismatchedModelId);
+                return Err(#krate::DecodeBodyError::MismatchedModelId);
             }
 
-            use native_model::Decode;
-            #with::decode(data).map_err(|e| native_model::DecodeBodyError::DecodeError {
+            use #krate::BorrowDecode;
+            #with::borrow_decode(data).map_err(|e| #krate::DecodeBodyError::DecodeError {
                 msg: format!("{}", e),
                 source: e.into(),
             })
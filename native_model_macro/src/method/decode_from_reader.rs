@@ -0,0 +1,39 @@
+use crate::method::generate_decode_upgrade;
+use crate::ModelAttributes;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub(crate) fn generate_native_model_decode_from_reader(attrs: &ModelAttributes) -> TokenStream {
+    let krate = &attrs.krate;
+    let id = &attrs.id;
+    let with = &attrs.with;
+    let decode_upgrade = generate_decode_upgrade(
+        attrs,
+        quote! { Self::native_model_decode_body_from_reader::<R, LIMIT>(reader, id) },
+        |model| {
+            quote! {
+                <#model as #krate::Model>::native_model_decode_upgrade_body_from_reader::<_, LIMIT>(reader, id, version)
+            }
+        },
+        None,
+        None,
+    );
+
+    let gen = quote! {
+        fn native_model_decode_body_from_reader<R: std::io::Read, const LIMIT: usize>(reader: R, id: u32) -> #krate::Result<Self> {
+            if id != #id {
+                return Err(#krate::DecodeBodyError::MismatchedModelId.into());
+            }
+
+            use #krate::Decode;
+            let result = #with::decode_from_reader_with_limit::<R, LIMIT>(reader)?;
+            Ok(result)
+        }
+
+        fn native_model_decode_upgrade_body_from_reader<R: std::io::Read, const LIMIT: usize>(reader: R, id: u32, version: u32) -> #krate::Result<Self> {
+            #decode_upgrade
+        }
+    };
+
+    gen
+}
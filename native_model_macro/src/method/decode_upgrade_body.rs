@@ -1,48 +1,222 @@
+use crate::method::{generate_upgrade_from_shortcuts, generate_upgrade_into_self};
 use crate::ModelAttributes;
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::{Generics, Path};
 
-pub(crate) fn generate_native_model_decode_upgrade_body(attrs: &ModelAttributes) -> TokenStream {
-    let native_model_from = attrs.from.clone();
-    let native_model_try_from = attrs.try_from.clone();
+/// The body of a method decoding the version `version` of the model and
+/// upgrading it, shared by the flavors of the decode.
+///
+/// `body` decodes the version of the model. `previous` decodes an older
+/// version into the given model and upgrades it up to this model, it gets `_`
+/// when the model is inferred from the `upgrade` function. The conversions
+/// are recorded into `report` if any, `context` is passed to the
+/// `upgrade_with` conversion if any.
+pub(crate) fn generate_decode_upgrade(
+    attrs: &ModelAttributes,
+    body: TokenStream,
+    previous: impl Fn(TokenStream) -> TokenStream,
+    report: Option<TokenStream>,
+    context: Option<TokenStream>,
+) -> TokenStream {
+    let krate = &attrs.krate;
 
-    let model_from_or_try_from = if let Some(from) = native_model_from {
+    // The step is recorded once the previous versions are upgraded, so the
+    // steps are ordered from the oldest version.
+    let record = |model: &Path, fallible: bool| match &report {
+        Some(report) => quote! {
+            #report.steps.push(#krate::UpgradeStep::new::<#model, Self>(
+                <#model as #krate::Model>::native_model_version(),
+                <Self as #krate::Model>::native_model_version(),
+                #fallible,
+            ));
+        },
+        None => quote! {},
+    };
+    let convert = |model: &Path, error: Option<&Path>| {
+        let decode = previous(quote! { #model });
+        let upgrade_into_self = generate_upgrade_into_self(krate, error);
+        let record = record(model, error.is_some());
         quote! {
-            #from::native_model_decode_upgrade_body(data, id, version).map(|a| a.into())
+            let result = #decode?;
+            #upgrade_into_self
+            #record
+            Ok(result)
         }
-    } else if let Some((try_from, error_try_from)) = native_model_try_from {
+    };
+
+    let upgrade_previous = if let Some(from) = &attrs.from {
+        convert(from, None)
+    } else if let Some((try_from, error)) = &attrs.try_from {
+        convert(try_from, Some(error))
+    } else if let Some(upgrade) = &attrs.upgrade {
+        let decode = previous(quote! { _ });
+        let record = report.as_ref().map(|report| {
+            quote! {
+                #report.steps.push(native_model_step);
+            }
+        });
         quote! {
-            let result = #try_from::native_model_decode_upgrade_body(data, id, version).map(|b| {
-                b.try_into()
-                    .map_err(|e: #error_try_from| native_model::UpgradeError {
+            let (result, native_model_step) = #krate::conversion::decode_upgrade(#upgrade, || #decode)?;
+            #record
+            Ok(result)
+        }
+    } else if let Some((upgrade_with, upgrade_context)) = &attrs.upgrade_with {
+        if let Some(context) = &context {
+            let decode = previous(quote! { #upgrade_with });
+            let record = record(upgrade_with, true);
+            quote! {
+                let upgrade_context = #context.downcast_ref::<#upgrade_context>().ok_or(
+                    #krate::Error::MissingUpgradeContext {
+                        context: std::any::type_name::<#upgrade_context>(),
+                    },
+                )?;
+                let result = #decode?;
+                let result = <Self as #krate::UpgradeWith<#upgrade_with, #upgrade_context>>::upgrade_with(result, upgrade_context)
+                    .map_err(|e| #krate::UpgradeError {
                         msg: format!("{}", e),
                         source: e.into(),
-                    })
-            })??;
-            Ok(result)
+                    })?;
+                #record
+                Ok(result)
+            }
+        } else {
+            quote! {
+                Err(#krate::Error::MissingUpgradeContext {
+                    context: std::any::type_name::<#upgrade_context>(),
+                })
+            }
         }
     } else {
         quote! {
-            Err(native_model::Error::UpgradeNotSupported {
+            Err(#krate::Error::UpgradeNotSupported {
                 from: version,
-                to: Self::native_model_version(),
+                to: <Self as #krate::Model>::native_model_version(),
             })
         }
     };
 
+    let upgrade_previous =
+        generate_upgrade_from_shortcuts(attrs, context.is_some(), upgrade_previous, convert);
+
+    quote! {
+        if version == <Self as #krate::Model>::native_model_version() {
+            let result = #body?;
+            Ok(result)
+        } else if version < <Self as #krate::Model>::native_model_version() {
+            #upgrade_previous
+        } else {
+            Err(#krate::Error::UpgradeNotSupported {
+                from: version,
+                to: <Self as #krate::Model>::native_model_version(),
+            })
+        }
+    }
+}
+
+pub(crate) fn generate_native_model_decode_upgrade_body(attrs: &ModelAttributes) -> TokenStream {
+    let krate = &attrs.krate;
+    let decode_upgrade = generate_decode_upgrade(
+        attrs,
+        quote! { Self::native_model_decode_body(data, id) },
+        |model| {
+            quote! {
+                <#model as #krate::Model>::native_model_decode_upgrade_body(data, id, version)
+            }
+        },
+        None,
+        None,
+    );
+
     let gen = quote! {
-        fn native_model_decode_upgrade_body(data: Vec<u8>, id: u32, version: u32) -> native_model::Result<Self> {
-            if version == Self::native_model_version() {
-                let result = Self::native_model_decode_body(data, id)?;
-                Ok(result)
-            } else if version < Self::native_model_version() {
-                #model_from_or_try_from
-            } else {
-                Err(native_model::Error::UpgradeNotSupported {
-                    from: version,
-                    to: Self::native_model_version(),
-                })
+        fn native_model_decode_upgrade_body(data: &[u8], id: u32, version: u32) -> #krate::Result<Self> {
+            #decode_upgrade
+        }
+    };
+
+    gen
+}
+
+pub(crate) fn generate_native_model_decode_upgrade_body_with_report(
+    attrs: &ModelAttributes,
+) -> TokenStream {
+    let krate = &attrs.krate;
+    let decode_upgrade = generate_decode_upgrade(
+        attrs,
+        quote! { Self::native_model_decode_body(data, id) },
+        |model| {
+            quote! {
+                <#model as #krate::Model>::native_model_decode_upgrade_body_with_report(data, id, version, report)
+            }
+        },
+        Some(quote! { report }),
+        None,
+    );
+
+    let gen = quote! {
+        fn native_model_decode_upgrade_body_with_report(data: &[u8], id: u32, version: u32, report: &mut #krate::UpgradeReport) -> #krate::Result<Self> {
+            #decode_upgrade
+        }
+    };
+
+    gen
+}
+
+pub(crate) fn generate_native_model_decode_upgrade_body_with_context(
+    attrs: &ModelAttributes,
+) -> TokenStream {
+    let krate = &attrs.krate;
+    let decode_upgrade = generate_decode_upgrade(
+        attrs,
+        quote! { Self::native_model_decode_body(data, id) },
+        |model| {
+            quote! {
+                <#model as #krate::Model>::native_model_decode_upgrade_body_with_context(data, id, version, context)
             }
+        },
+        None,
+        Some(quote! { context }),
+    );
+
+    let gen = quote! {
+        fn native_model_decode_upgrade_body_with_context(data: &[u8], id: u32, version: u32, context: &dyn std::any::Any) -> #krate::Result<Self> {
+            #decode_upgrade
+        }
+    };
+
+    gen
+}
+
+pub(crate) fn generate_native_model_decode_upgrade_borrowed_body(
+    attrs: &ModelAttributes,
+    generics: &Generics,
+) -> TokenStream {
+    let krate = &attrs.krate;
+
+    // Models without lifetimes are upgraded by their `Model` implementation.
+    if generics.lifetimes().next().is_none() {
+        return quote! {
+            fn native_model_decode_upgrade_borrowed_body(data: &'native_model_de [u8], id: u32, version: u32) -> #krate::Result<Self> {
+                <Self as #krate::Model>::native_model_decode_upgrade_body(data, id, version)
+            }
+        };
+    }
+
+    let decode_upgrade = generate_decode_upgrade(
+        attrs,
+        quote! { Self::native_model_decode_borrowed_body(data, id) },
+        |model| {
+            quote! {
+                <#model as #krate::BorrowModel<'native_model_de>>::native_model_decode_upgrade_borrowed_body(data, id, version)
+            }
+        },
+        None,
+        None,
+    );
+
+    let gen = quote! {
+        fn native_model_decode_upgrade_borrowed_body(data: &'native_model_de [u8], id: u32, version: u32) -> #krate::Result<Self> {
+            #decode_upgrade
         }
     };
 
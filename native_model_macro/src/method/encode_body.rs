@@ -3,15 +3,30 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 pub(crate) fn generate_native_model_encode_body(attrs: &ModelAttributes) -> TokenStream {
-    let with = attrs.with.clone().expect("`with` is required");
+    let krate = &attrs.krate;
+    let with = &attrs.with;
     let gen = quote! {
-        fn native_model_encode_body(&self) -> std::result::Result<Vec<u8>, native_model::EncodeBodyError> {
-            use native_model::Encode;
-            #with::encode(self).map_err(|e| native_model::EncodeBodyError {
+        fn native_model_encode_body(&self) -> std::result::Result<Vec<u8>, #krate::EncodeBodyError> {
+            use #krate::Encode;
+            #with::encode(self).map_err(|e| #krate::EncodeBodyError {
                 msg: format!("{}", e),
                 source: e.into(),
             })
         }
+
+        fn native_model_encode_body_into(&self, buffer: &mut Vec<u8>) -> std::result::Result<(), #krate::EncodeBodyError> {
+            use #krate::Encode;
+            #with::encode_into(self, buffer).map_err(|e| #krate::EncodeBodyError {
+                msg: format!("{}", e),
+                source: e.into(),
+            })
+        }
+
+        fn native_model_encode_body_to_writer<W: std::io::Write>(&self, writer: W) -> #krate::Result<()> {
+            use #krate::Encode;
+            #with::encode_to_writer(self, writer)?;
+            Ok(())
+        }
     };
 
     gen
@@ -3,18 +3,19 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 pub(crate) fn generate_native_model_encode_downgrade_body(attrs: &ModelAttributes) -> TokenStream {
-    let native_model_from = attrs.from.clone();
-    let native_model_try_from = attrs.try_from.clone();
+    let krate = &attrs.krate;
+    let native_model_from = attrs.from.clone().or(attrs.downgrade_to.clone());
+    let native_model_try_from = attrs.try_from.clone().or(attrs.try_downgrade_to.clone());
 
     let model_from_or_try_from = if let Some(from) = native_model_from {
         quote! {
-            #from::native_model_encode_downgrade_body(self.into(), version)
+            <#from as #krate::Model>::native_model_encode_downgrade_body(self.into(), version)
         }
     } else if let Some((try_from, error_try_from)) = native_model_try_from {
         quote! {
-            let result = #try_from::native_model_encode_downgrade_body(
+            let result = <#try_from as #krate::Model>::native_model_encode_downgrade_body(
                 self.try_into()
-                    .map_err(|e: #error_try_from| native_model::DowngradeError {
+                    .map_err(|e: #error_try_from| #krate::DowngradeError {
                         msg: format!("{}", e),
                         source: e.into(),
                     })?,
@@ -22,9 +23,13 @@ pub(crate) fn generate_native_model_encode_downgrade_body(attrs: &ModelAttribute
             )?;
             Ok(result)
         }
+    } else if let Some(downgrade) = &attrs.downgrade {
+        quote! {
+            #krate::conversion::encode_downgrade_body(#downgrade, self, version)
+        }
     } else {
         quote! {
-            Err(native_model::Error::DowngradeNotSupported {
+            Err(#krate::Error::DowngradeNotSupported {
                 from: version,
                 to: Self::native_model_version(),
             })
@@ -32,14 +37,14 @@ pub(crate) fn generate_native_model_encode_downgrade_body(attrs: &ModelAttribute
     };
 
     let gen = quote! {
-        fn native_model_encode_downgrade_body(self, version: u32) -> native_model::Result<Vec<u8>> {
+        fn native_model_encode_downgrade_body(self, version: u32) -> #krate::Result<Vec<u8>> {
             if version == Self::native_model_version() {
                 let result = self.native_model_encode_body()?;
                 Ok(result)
             } else if version < Self::native_model_version() {
                 #model_from_or_try_from
             } else {
-                Err(native_model::Error::DowngradeNotSupported {
+                Err(#krate::Error::DowngradeNotSupported {
                     from: version,
                     to: Self::native_model_version(),
                 })
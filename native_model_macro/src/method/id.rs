@@ -1,17 +1,38 @@
 use crate::ModelAttributes;
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::{Expr, ExprLit, Lit};
 
 pub(crate) fn generate_native_model_id(model_attributes: &ModelAttributes) -> TokenStream {
-    let native_model_id = model_attributes.id.clone().unwrap();
+    let native_model_id = &model_attributes.id;
+    let native_model_id_str = generate_u32_str(native_model_id);
     let gen = quote! {
+        const ID: u32 = #native_model_id;
+
         fn native_model_id() -> u32 {
             #native_model_id
         }
 
         fn native_model_id_str() -> &'static str {
-            stringify!(#native_model_id)
+            #native_model_id_str
         }
     };
     gen
 }
+
+/// Body of a function returning `value`, a `u32` literal or constant, as a
+/// `&'static str`.
+pub(crate) fn generate_u32_str(value: &Expr) -> TokenStream {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => {
+            let digits = int.base10_digits();
+            quote! { #digits }
+        }
+        _ => quote! {
+            static VALUE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+            VALUE.get_or_init(|| (#value).to_string())
+        },
+    }
+}
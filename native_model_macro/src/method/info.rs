@@ -0,0 +1,26 @@
+use crate::ModelAttributes;
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::Ident;
+
+pub(crate) fn generate_native_model_info(
+    model_attributes: &ModelAttributes,
+    struct_name: &Ident,
+) -> TokenStream {
+    let krate = &model_attributes.krate;
+    let type_name = struct_name.to_string();
+    let codec = model_attributes
+        .with
+        .to_token_stream()
+        .to_string()
+        .replace(' ', "");
+    let gen = quote! {
+        const INFO: #krate::ModelInfo = #krate::ModelInfo::new(
+            <Self as #krate::Model>::ID,
+            <Self as #krate::Model>::VERSION,
+            #type_name,
+            #codec,
+        );
+    };
+    gen
+}
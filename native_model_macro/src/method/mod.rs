@@ -1,13 +1,19 @@
 mod decode_body;
+mod decode_from_reader;
 mod decode_upgrade_body;
 mod encode_body;
 mod encode_downgrade_body;
 mod id;
+mod info;
+mod upgrade_from;
 mod version;
 
 pub(crate) use decode_body::*;
+pub(crate) use decode_from_reader::*;
 pub(crate) use decode_upgrade_body::*;
 pub(crate) use encode_body::*;
 pub(crate) use encode_downgrade_body::*;
 pub(crate) use id::*;
+pub(crate) use info::*;
+pub(crate) use upgrade_from::*;
 pub(crate) use version::*;
@@ -0,0 +1,162 @@
+use crate::ModelAttributes;
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::Path;
+
+/// The models converted directly into this version by `upgrade_from` and
+/// `try_upgrade_from`, along with the error type of the fallible conversions.
+pub(crate) fn upgrades_from(attrs: &ModelAttributes) -> Vec<(&Path, Option<&Path>)> {
+    attrs
+        .upgrade_from
+        .iter()
+        .map(|upgrade_from| (upgrade_from, None))
+        .chain(
+            attrs
+                .try_upgrade_from
+                .iter()
+                .map(|(try_upgrade_from, error)| (try_upgrade_from, Some(error))),
+        )
+        .collect()
+}
+
+/// Converts `result`, a model of the previous version, into `Self`.
+pub(crate) fn generate_upgrade_into_self(krate: &Path, error: Option<&Path>) -> TokenStream {
+    match error {
+        Some(error) => quote! {
+            let result: Self = result
+                .try_into()
+                .map_err(|e: #error| #krate::UpgradeError {
+                    msg: format!("{}", e),
+                    source: e.into(),
+                })?;
+        },
+        None => quote! {
+            let result: Self = result.into();
+        },
+    }
+}
+
+/// The cost of upgrading `version` to the previous version of the model,
+/// given by `from`, `try_from`, `upgrade` or `upgrade_with`. The paths
+/// through `upgrade_with` conversions are only counted with `context`.
+fn generate_previous_cost(attrs: &ModelAttributes, context: bool) -> TokenStream {
+    let krate = &attrs.krate;
+    let previous = attrs
+        .from
+        .as_ref()
+        .or(attrs.try_from.as_ref().map(|(try_from, _)| try_from))
+        .or(attrs
+            .upgrade_with
+            .as_ref()
+            .filter(|_| context)
+            .map(|(upgrade_with, _)| upgrade_with));
+    if let Some(previous) = previous {
+        quote! {
+            if <#previous as #krate::Model>::VERSION < <Self as #krate::Model>::VERSION {
+                <#previous as #krate::Model>::native_model_upgrade_cost_with(version, #context)
+            } else {
+                None
+            }
+        }
+    } else if let Some(upgrade) = &attrs.upgrade {
+        quote! {
+            #krate::conversion::upgrade_cost(#upgrade, version, #context)
+        }
+    } else {
+        quote! { None }
+    }
+}
+
+pub(crate) fn generate_native_model_upgrade_cost(attrs: &ModelAttributes) -> TokenStream {
+    let krate = &attrs.krate;
+    let add_previous = if let Some(previous) = attrs
+        .from
+        .as_ref()
+        .or(attrs.try_from.as_ref().map(|(try_from, _)| try_from))
+    {
+        quote! { costs.add::<#previous>(); }
+    } else if let Some((upgrade_with, _)) = &attrs.upgrade_with {
+        quote! { costs.add_with_context::<#upgrade_with>(); }
+    } else if let Some(upgrade) = &attrs.upgrade {
+        quote! { costs.add_upgrade(#upgrade); }
+    } else {
+        quote! {}
+    };
+    let upgrades_from = upgrades_from(attrs);
+    let upgrade_from = upgrades_from.iter().map(|(upgrade_from, _)| upgrade_from);
+
+    // The static is shared by the instances of generic models, which have the
+    // same upgrades.
+    let gen = quote! {
+        fn native_model_upgrade_costs(context: bool) -> &'static [(u32, u32)] {
+            static NATIVE_MODEL_UPGRADE_COSTS: [std::sync::OnceLock<Vec<(u32, u32)>>; 2] =
+                [std::sync::OnceLock::new(), std::sync::OnceLock::new()];
+            NATIVE_MODEL_UPGRADE_COSTS[usize::from(context)].get_or_init(|| {
+                let mut costs = #krate::conversion::UpgradeCosts::new::<Self>(context);
+                #add_previous
+                #(
+                    costs.add::<#upgrade_from>();
+                )*
+                costs.into_costs()
+            })
+        }
+    };
+
+    gen
+}
+
+/// Upgrades through the `upgrade_from` or `try_upgrade_from` model with the
+/// shortest path from `version`, generated by `shortcut` with the error type
+/// of fallible conversions. Falls back to `previous`, the upgrade through
+/// `from`, `try_from`, `upgrade` or `upgrade_with`, if it is as short or if
+/// none applies. The paths through `upgrade_with` conversions are only
+/// counted with `context`.
+pub(crate) fn generate_upgrade_from_shortcuts(
+    attrs: &ModelAttributes,
+    context: bool,
+    previous: TokenStream,
+    shortcut: impl Fn(&Path, Option<&Path>) -> TokenStream,
+) -> TokenStream {
+    let krate = &attrs.krate;
+    let upgrades_from = upgrades_from(attrs);
+    if upgrades_from.is_empty() {
+        return previous;
+    }
+
+    let previous_cost = generate_previous_cost(attrs, context);
+    let indexes = (0..upgrades_from.len())
+        .map(Literal::usize_unsuffixed)
+        .collect::<Vec<_>>();
+    let upgrade_from = upgrades_from.iter().map(|(upgrade_from, _)| upgrade_from);
+    let shortcuts = upgrades_from
+        .iter()
+        .map(|(upgrade_from, error)| shortcut(upgrade_from, *error));
+
+    quote! {
+        let native_model_costs = [
+            #(
+                if <#upgrade_from as #krate::Model>::VERSION < <Self as #krate::Model>::VERSION {
+                    <#upgrade_from as #krate::Model>::native_model_upgrade_cost_with(version, #context)
+                } else {
+                    None
+                },
+            )*
+        ];
+        let mut native_model_best: Option<u32> = #previous_cost;
+        let mut native_model_shortcut: Option<usize> = None;
+        for (index, cost) in native_model_costs.iter().enumerate() {
+            if let Some(cost) = *cost {
+                if native_model_best.map_or(true, |best| cost < best) {
+                    native_model_best = Some(cost);
+                    native_model_shortcut = Some(index);
+                }
+            }
+        }
+        match native_model_shortcut {
+            #(
+                Some(#indexes) => { #shortcuts }
+            )*
+            _ => { #previous }
+        }
+    }
+}
@@ -1,16 +1,20 @@
+use crate::method::generate_u32_str;
 use crate::ModelAttributes;
 use proc_macro2::TokenStream;
 use quote::quote;
 
 pub(crate) fn generate_native_model_version(model_attributes: &ModelAttributes) -> TokenStream {
-    let native_model_version = model_attributes.version.clone().unwrap();
+    let native_model_version = &model_attributes.version;
+    let native_model_version_str = generate_u32_str(native_model_version);
     let gen = quote! {
+        const VERSION: u32 = #native_model_version;
+
         fn native_model_version() -> u32 {
             #native_model_version
         }
 
         fn native_model_version_str() -> &'static str {
-            stringify!(#native_model_version)
+            #native_model_version_str
         }
     };
     gen
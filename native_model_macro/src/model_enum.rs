@@ -0,0 +1,128 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, Path, Result, Type};
+
+/// Parses the `#[native_model(crate = path)]` attributes of the enum, the path
+/// of `native_model` in the generated code.
+fn parse_crate_path(ast: &DeriveInput) -> Result<Path> {
+    let mut krate = None;
+    for attr in &ast.attrs {
+        if !attr.path().is_ident("native_model") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("crate") {
+                return Err(meta.error("unknown attribute of `NativeModelEnum`, expected `crate`"));
+            }
+            if krate.is_some() {
+                return Err(meta.error("duplicate `crate` attribute"));
+            }
+            krate = Some(meta.value()?.parse()?);
+            Ok(())
+        })?;
+    }
+    Ok(krate.unwrap_or_else(|| parse_quote!(native_model)))
+}
+
+pub(crate) fn generate_native_model_enum(ast: &DeriveInput) -> Result<TokenStream> {
+    let enum_name = &ast.ident;
+    let krate = &parse_crate_path(ast)?;
+    if !ast.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &ast.generics,
+            "`NativeModelEnum` doesn't support generic enums",
+        ));
+    }
+    let data = match &ast.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                enum_name,
+                "`NativeModelEnum` can only be derived for enums",
+            ))
+        }
+    };
+
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Ok((&variant.ident, &fields.unnamed[0].ty))
+            }
+            _ => Err(Error::new_spanned(
+                variant,
+                format!(
+                    "variant `{}` must hold a single model, i.e. `{}(Model)`",
+                    variant.ident, variant.ident
+                ),
+            )),
+        })
+        .collect::<Result<Vec<(_, &Type)>>>()?;
+    if variants.is_empty() {
+        return Err(Error::new_spanned(
+            enum_name,
+            format!("`{}` must have at least one variant", enum_name),
+        ));
+    }
+
+    // Two variants with the same id can't be told apart when decoding.
+    let mut distinct_ids = vec![];
+    for (i, (variant_a, model_a)) in variants.iter().enumerate() {
+        for (variant_b, model_b) in &variants[i + 1..] {
+            let message = format!(
+                "Variants `{}` and `{}` of `{}` have the same model id",
+                variant_a, variant_b, enum_name
+            );
+            distinct_ids.push(quote! {
+                assert!(
+                    <#model_a as #krate::Model>::ID
+                        != <#model_b as #krate::Model>::ID,
+                    #message
+                );
+            });
+        }
+    }
+
+    let decode_variants = variants.iter().map(|(variant, model)| {
+        quote! {
+            if id == <#model as #krate::Model>::native_model_id() {
+                let (model, version) = #krate::decode_slice::<#model>(data)?;
+                return Ok((#enum_name::#variant(model), version));
+            }
+        }
+    });
+
+    let encode_variants = variants.iter().map(|(variant, _)| {
+        quote! {
+            #enum_name::#variant(model) => #krate::encode(model),
+        }
+    });
+
+    let gen = quote! {
+        const _: () = {
+            #(#distinct_ids)*
+        };
+
+        impl #enum_name {
+            /// Decodes the model of the variant matching the id of `data`,
+            /// upgraded to its version, and returns the version ([`u32`]) it
+            /// was encoded with.
+            pub fn native_model_decode(data: impl AsRef<[u8]>) -> #krate::Result<(Self, u32)> {
+                let data = data.as_ref();
+                let id = #krate::peek(data)?.id;
+                #(#decode_variants)*
+                Err(#krate::Error::UnknownModelId { id })
+            }
+
+            /// Encodes the model of the variant.
+            pub fn native_model_encode(&self) -> #krate::Result<Vec<u8>> {
+                match self {
+                    #(#encode_variants)*
+                }
+            }
+        }
+    };
+
+    Ok(gen)
+}
@@ -40,6 +40,19 @@ impl<T: serde::Serialize> super::Encode<T> for Bincode {
     fn encode(obj: &T) -> Result<Vec<u8>, Self::Error> {
         bincode_1_3::serialize(obj)
     }
+
+    /// Serializes a type at the end of `buffer` using the `bincode` `1.3` crate.
+    fn encode_into(obj: &T, buffer: &mut Vec<u8>) -> Result<(), Self::Error> {
+        bincode_1_3::serialize_into(buffer, obj)
+    }
+
+    /// Serializes a type into `writer` using the `bincode` `1.3` crate.
+    fn encode_to_writer<W: std::io::Write>(obj: &T, writer: W) -> std::io::Result<()> {
+        bincode_1_3::serialize_into(writer, obj).map_err(|e| match *e {
+            bincode_1_3::ErrorKind::Io(e) => e,
+            e => super::other_io_error(e),
+        })
+    }
 }
 
 #[cfg(all(feature = "serde", feature = "bincode_1_3"))]
@@ -47,6 +60,40 @@ impl<T: for<'de> serde::Deserialize<'de>> super::Decode<T> for Bincode {
     type Error = bincode_1_3::Error;
     /// Deserializes a type from bytes using the `bincode` `1.3` crate.
     fn decode(data: Vec<u8>) -> Result<T, Self::Error> {
-        bincode_1_3::deserialize(&data[..])
+        Self::decode_slice(&data)
+    }
+
+    /// Deserializes a type from borrowed bytes using the `bincode` `1.3` crate.
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        bincode_1_3::deserialize(data)
+    }
+
+    /// Deserializes a type from `reader` using the `bincode` `1.3` crate.
+    ///
+    /// Same options as [`bincode_1_3::deserialize_from`], with a limit of
+    /// `LIMIT` bytes read or allocated.
+    fn decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+    ) -> std::io::Result<T> {
+        use bincode_1_3::Options;
+
+        bincode_1_3::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(LIMIT as u64)
+            .deserialize_from(reader)
+            .map_err(|e| match *e {
+                bincode_1_3::ErrorKind::Io(e) => e,
+                e => super::invalid_data_io_error(e),
+            })
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "bincode_1_3"))]
+impl<'de, T: serde::Deserialize<'de>> super::BorrowDecode<'de, T> for Bincode {
+    type Error = bincode_1_3::Error;
+    /// Deserializes a type borrowing from bytes using the `bincode` `1.3` crate.
+    fn borrow_decode(data: &'de [u8]) -> Result<T, Self::Error> {
+        bincode_1_3::deserialize(data)
     }
 }
@@ -42,6 +42,25 @@ impl<T: serde::Serialize> super::Encode<T> for Bincode {
     fn encode(obj: &T) -> Result<Vec<u8>, Self::Error> {
         bincode_2::serde::encode_to_vec(obj, bincode_2::config::standard())
     }
+
+    /// Serializes a type at the end of `buffer` using the `bincode` `2.0` crate.
+    fn encode_into(obj: &T, buffer: &mut Vec<u8>) -> Result<(), Self::Error> {
+        bincode_2::serde::encode_into_std_write(obj, buffer, bincode_2::config::standard())?;
+        Ok(())
+    }
+
+    /// Serializes a type into `writer` using the `bincode` `2.0` crate.
+    fn encode_to_writer<W: std::io::Write>(obj: &T, mut writer: W) -> std::io::Result<()> {
+        match bincode_2::serde::encode_into_std_write(
+            obj,
+            &mut writer,
+            bincode_2::config::standard(),
+        ) {
+            Ok(_) => Ok(()),
+            Err(bincode_2::error::EncodeError::Io { inner, .. }) => Err(inner),
+            Err(e) => Err(super::other_io_error(e)),
+        }
+    }
 }
 
 #[cfg(all(feature = "serde", feature = "bincode_2"))]
@@ -49,6 +68,73 @@ impl<T: for<'de> serde::Deserialize<'de>> super::Decode<T> for Bincode {
     type Error = bincode_2::error::DecodeError;
     /// Deserializes a type from bytes using the `bincode` `2.0` crate.
     fn decode(data: Vec<u8>) -> Result<T, Self::Error> {
-        Ok(bincode_2::serde::decode_from_slice(&data, bincode_2::config::standard())?.0)
+        Self::decode_slice(&data)
+    }
+
+    /// Deserializes a type from borrowed bytes using the `bincode` `2.0` crate.
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        decode_from_slice(data)
+    }
+
+    /// Deserializes a type from `reader` using the `bincode` `2.0` crate.
+    ///
+    /// Same configuration as [`Decode::decode_slice`](super::Decode::decode_slice),
+    /// with a limit of `LIMIT` bytes claimed from `reader`.
+    fn decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        mut reader: R,
+    ) -> std::io::Result<T> {
+        let config = bincode_2::config::standard().with_limit::<LIMIT>();
+        bincode_2::serde::decode_from_std_read(&mut reader, config).map_err(|e| match e {
+            bincode_2::error::DecodeError::Io { inner, .. } => inner,
+            e => super::invalid_data_io_error(e),
+        })
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "bincode_2"))]
+impl<'de, T: serde::Deserialize<'de>> super::BorrowDecode<'de, T> for Bincode {
+    type Error = bincode_2::error::DecodeError;
+    /// Deserializes a type borrowing from bytes using the `bincode` `2.0` crate.
+    fn borrow_decode(data: &'de [u8]) -> Result<T, Self::Error> {
+        decode_from_slice(data)
     }
 }
+
+/// Upper bound of the bytes `bincode` `2.0` claims for each byte of input: a
+/// varint encoded `u128` takes a single byte but claims 16.
+#[cfg(all(feature = "serde", feature = "bincode_2"))]
+const CLAIM_PER_INPUT_BYTE: usize = 16;
+
+/// Limit of [`decode_from_slice`] for inputs whose claim exceeds
+/// [`DEFAULT_READER_LIMIT`](crate::DEFAULT_READER_LIMIT): 64 GiB, the claim of
+/// a 4 GiB input, or the whole address space on 32 bit targets.
+#[cfg(all(feature = "serde", feature = "bincode_2"))]
+const LARGE_SLICE_LIMIT: usize = if usize::BITS > 36 {
+    (1u64 << 36) as usize
+} else {
+    usize::MAX
+};
+
+/// Deserializes `data` with a limit covering its length.
+///
+/// `bincode` `2.0` allocates strings and byte buffers from the length prefix
+/// before reading them, so an unlimited configuration aborts the process on a
+/// corrupted prefix. Valid data never claims more than
+/// [`CLAIM_PER_INPUT_BYTE`] bytes per input byte: inputs within
+/// [`DEFAULT_READER_LIMIT`](crate::DEFAULT_READER_LIMIT) once multiplied are
+/// decoded with that limit, larger ones with [`LARGE_SLICE_LIMIT`].
+#[cfg(all(feature = "serde", feature = "bincode_2"))]
+fn decode_from_slice<'de, T: serde::Deserialize<'de>>(
+    data: &'de [u8],
+) -> Result<T, bincode_2::error::DecodeError> {
+    use bincode_2::serde::borrow_decode_from_slice;
+
+    let config = bincode_2::config::standard();
+    let claim = data.len().saturating_mul(CLAIM_PER_INPUT_BYTE);
+    let (result, _) = if claim <= crate::DEFAULT_READER_LIMIT {
+        borrow_decode_from_slice(data, config.with_limit::<{ crate::DEFAULT_READER_LIMIT }>())?
+    } else {
+        borrow_decode_from_slice(data, config.with_limit::<LARGE_SLICE_LIMIT>())?
+    };
+    Ok(result)
+}
@@ -1,6 +1,9 @@
 //! Traits and implementations for encoding types into a series of bytes and
 //! decoding bytes back into types.
 
+// The examples of the codecs are separated from their struct by a blank line.
+#![allow(clippy::empty_line_after_doc_comments)]
+
 #[cfg(any(all(feature = "serde", feature = "bincode_1_3"), doc))]
 pub mod bincode_1_3;
 #[cfg(any(all(feature = "serde", feature = "bincode_2"), doc))]
@@ -34,6 +37,114 @@ pub trait Encode<T> {
     /// The errors returned from this function depend on the trait implementor
     /// (the serializer), i.e. `bincode_2`.
     fn encode(obj: &T) -> Result<Vec<u8>, Self::Error>;
+
+    /// Encodes a `T` type at the end of `buffer`.
+    ///
+    /// The default implementation calls [`Encode::encode`] and copies the
+    /// bytes into `buffer`, implementors able to serialize into a [`Vec<u8>`]
+    /// should override it to avoid the intermediate allocation.
+    ///
+    /// # Errors
+    ///
+    /// The errors returned from this function depend on the trait implementor
+    /// (the serializer), i.e. `bincode_2`.
+    fn encode_into(obj: &T, buffer: &mut Vec<u8>) -> Result<(), Self::Error> {
+        buffer.extend_from_slice(&Self::encode(obj)?);
+        Ok(())
+    }
+
+    /// Encodes a `T` type into `writer`.
+    ///
+    /// The default implementation calls [`Encode::encode`] and writes the
+    /// bytes, implementors able to serialize into a [`std::io::Write`] should
+    /// override it to avoid buffering the whole value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `writer`, the errors of the serializer are
+    /// wrapped in a [`std::io::Error`] of kind [`std::io::ErrorKind::Other`].
+    fn encode_to_writer<W: std::io::Write>(obj: &T, mut writer: W) -> std::io::Result<()>
+    where
+        Self::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let data = Self::encode(obj).map_err(other_io_error)?;
+        writer.write_all(&data)
+    }
+}
+
+/// Wraps an error of a codec in a [`std::io::Error`].
+pub(crate) fn other_io_error(
+    error: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, error)
+}
+
+/// Wraps an error of a decoder in a [`std::io::Error`].
+pub(crate) fn invalid_data_io_error(
+    error: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
+
+/// Writer keeping the last error of the wrapped writer, for serializers which
+/// don't give it back.
+#[cfg(any(
+    all(feature = "serde", feature = "postcard_1_0"),
+    all(feature = "serde", feature = "rmp_serde_1_3")
+))]
+pub(crate) struct KeepIoError<W> {
+    writer: W,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(any(
+    all(feature = "serde", feature = "postcard_1_0"),
+    all(feature = "serde", feature = "rmp_serde_1_3")
+))]
+impl<W> KeepIoError<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Returns the error of the writer if any, otherwise the error of the
+    /// serializer wrapped in a [`std::io::Error`].
+    pub(crate) fn into_result<E>(self, result: Result<(), E>) -> std::io::Result<()>
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        match (result, self.error) {
+            (Ok(()), _) => Ok(()),
+            (Err(_), Some(error)) => Err(error),
+            (Err(error), None) => Err(other_io_error(error)),
+        }
+    }
+
+    fn keep(&mut self, error: std::io::Error) -> std::io::Error {
+        let kind = error.kind();
+        self.error = Some(error);
+        kind.into()
+    }
+}
+
+#[cfg(any(
+    all(feature = "serde", feature = "postcard_1_0"),
+    all(feature = "serde", feature = "rmp_serde_1_3")
+))]
+impl<W: std::io::Write> std::io::Write for KeepIoError<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf).map_err(|e| self.keep(e))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.writer.write_all(buf).map_err(|e| self.keep(e))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush().map_err(|e| self.keep(e))
+    }
 }
 
 /// Decode trait for your own decoding method.
@@ -47,7 +158,11 @@ pub trait Encode<T> {
 /// impl<T: for<'a> Deserialize<'a>> native_model::Decode<T> for Bincode {
 ///     type Error = DecodeError;
 ///     fn decode(data: Vec<u8>) -> Result<T, DecodeError> {
-///         Ok(decode_from_slice(&data, standard())?.0)
+///         Self::decode_slice(&data)
+///     }
+///
+///     fn decode_slice(data: &[u8]) -> Result<T, DecodeError> {
+///         Ok(decode_from_slice(data, standard())?.0)
 ///     }
 /// }
 pub trait Decode<T> {
@@ -59,4 +174,90 @@ pub trait Decode<T> {
     /// The errors returned from this function depend on the trait implementor
     /// (the deserializer), i.e. `bincode_2`.
     fn decode(data: Vec<u8>) -> Result<T, Self::Error>;
+
+    /// Decodes a borrowed series of bytes back into a `T` type.
+    ///
+    /// This is the method used by [`native_model`](crate::native_model) to
+    /// decode the body of a model. The default implementation copies `data`
+    /// into a [`Vec<u8>`] and calls [`Decode::decode`], implementors able to
+    /// deserialize from a slice should override it to avoid the copy.
+    ///
+    /// # Errors
+    ///
+    /// The errors returned from this function depend on the trait implementor
+    /// (the deserializer), i.e. `bincode_2`.
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        Self::decode(data.to_vec())
+    }
+
+    /// Decodes a `T` type from `reader`, reading at most
+    /// [`DEFAULT_READER_LIMIT`](crate::DEFAULT_READER_LIMIT) bytes, see
+    /// [`Decode::decode_from_reader_with_limit`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Decode::decode_from_reader_with_limit`].
+    fn decode_from_reader<R: std::io::Read>(reader: R) -> std::io::Result<T>
+    where
+        Self::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self::decode_from_reader_with_limit::<R, { crate::DEFAULT_READER_LIMIT }>(reader)
+    }
+
+    /// Decodes a `T` type from `reader`, reading at most `LIMIT` bytes.
+    ///
+    /// The default implementation reads `reader` to the end and calls
+    /// [`Decode::decode`], implementors able to deserialize from a
+    /// [`std::io::Read`] should override it to only read the bytes of the
+    /// value.
+    ///
+    /// Deserializers allocate the lengths read from the data before reading
+    /// the bytes backing them and the length of a reader isn't known
+    /// beforehand, implementors should bound these allocations by `LIMIT` as
+    /// well. Prefer [`Decode::decode_slice`] for untrusted sources.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `reader`, the errors of the deserializer and the
+    /// `LIMIT` being exceeded are wrapped in a [`std::io::Error`] of kind
+    /// [`std::io::ErrorKind::InvalidData`].
+    fn decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+    ) -> std::io::Result<T>
+    where
+        Self::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let data = crate::read_to_end_with_limit::<LIMIT>(reader)?;
+        Self::decode(data).map_err(invalid_data_io_error)
+    }
+}
+
+/// Decode trait for decoding methods able to borrow from the bytes.
+///
+/// Required by models with lifetime parameters, i.e. holding `&'de str` or
+/// `Cow<'de, str>` fields, see [`decode_borrowed`](crate::decode_borrowed).
+///
+/// Example:
+/// ```rust
+/// use bincode_2::{error::DecodeError,serde::borrow_decode_from_slice, config::standard};
+/// use serde::Deserialize;
+/// pub struct Bincode;
+///
+/// impl<'de, T: Deserialize<'de>> native_model::BorrowDecode<'de, T> for Bincode {
+///     type Error = DecodeError;
+///     fn borrow_decode(data: &'de [u8]) -> Result<T, DecodeError> {
+///         Ok(borrow_decode_from_slice(data, standard())?.0)
+///     }
+/// }
+/// ```
+pub trait BorrowDecode<'de, T> {
+    type Error;
+    /// Decodes a series of bytes back into a `T` type which may borrow from
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// The errors returned from this function depend on the trait implementor
+    /// (the deserializer), i.e. `bincode_2`.
+    fn borrow_decode(data: &'de [u8]) -> Result<T, Self::Error>;
 }
@@ -39,6 +39,20 @@ impl<T: serde::Serialize> super::Encode<T> for PostCard {
     fn encode(obj: &T) -> Result<Vec<u8>, Self::Error> {
         postcard_1_0::to_allocvec(obj)
     }
+
+    /// Serializes a type at the end of `buffer` using the `postcard` `1.0` crate.
+    fn encode_into(obj: &T, buffer: &mut Vec<u8>) -> Result<(), Self::Error> {
+        postcard_1_0::to_io(obj, buffer)?;
+        Ok(())
+    }
+
+    /// Serializes a type into `writer` using the `postcard` `1.0` crate.
+    fn encode_to_writer<W: std::io::Write>(obj: &T, writer: W) -> std::io::Result<()> {
+        // `postcard` reports the errors of the writer as a full buffer.
+        let mut writer = super::KeepIoError::new(writer);
+        let result = postcard_1_0::to_io(obj, &mut writer).map(|_| ());
+        writer.into_result(result)
+    }
 }
 
 #[cfg(all(feature = "serde", feature = "postcard_1_0"))]
@@ -46,6 +60,32 @@ impl<T: for<'de> serde::Deserialize<'de>> super::Decode<T> for PostCard {
     type Error = postcard_1_0::Error;
     /// Deserializes a type from bytes using the `postcard` `1.0` crate.
     fn decode(data: Vec<u8>) -> Result<T, Self::Error> {
-        postcard_1_0::from_bytes(&data)
+        Self::decode_slice(&data)
+    }
+
+    /// Deserializes a type from borrowed bytes using the `postcard` `1.0` crate.
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        postcard_1_0::from_bytes(data)
+    }
+
+    /// Deserializes a type from `reader` using the `postcard` `1.0` crate.
+    ///
+    /// `postcard` needs a scratch buffer as large as the biggest field to
+    /// deserialize from a reader, so at most `LIMIT` bytes of `reader` are
+    /// read to the end instead.
+    fn decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+    ) -> std::io::Result<T> {
+        let data = crate::read_to_end_with_limit::<LIMIT>(reader)?;
+        postcard_1_0::from_bytes(&data).map_err(super::invalid_data_io_error)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "postcard_1_0"))]
+impl<'de, T: serde::Deserialize<'de>> super::BorrowDecode<'de, T> for PostCard {
+    type Error = postcard_1_0::Error;
+    /// Deserializes a type borrowing from bytes using the `postcard` `1.0` crate.
+    fn borrow_decode(data: &'de [u8]) -> Result<T, Self::Error> {
+        postcard_1_0::from_bytes(data)
     }
 }
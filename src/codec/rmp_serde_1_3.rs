@@ -37,6 +37,18 @@ impl<T: serde::Serialize> crate::Encode<T> for RmpSerde {
     fn encode(obj: &T) -> Result<Vec<u8>, Self::Error> {
         rmp_serde_1_3::encode::to_vec(obj)
     }
+
+    /// Serializes a type at the end of `buffer` using the `rmp-serde` `1.3` crate.
+    fn encode_into(obj: &T, buffer: &mut Vec<u8>) -> Result<(), Self::Error> {
+        rmp_serde_1_3::encode::write(buffer, obj)
+    }
+
+    /// Serializes a type into `writer` using the `rmp-serde` `1.3` crate.
+    fn encode_to_writer<W: std::io::Write>(obj: &T, writer: W) -> std::io::Result<()> {
+        let mut writer = crate::codec::KeepIoError::new(writer);
+        let result = rmp_serde_1_3::encode::write(&mut writer, obj);
+        writer.into_result(result)
+    }
 }
 
 #[cfg(all(feature = "serde", feature = "rmp_serde_1_3"))]
@@ -44,7 +56,30 @@ impl<T: for<'de> serde::Deserialize<'de>> crate::Decode<T> for RmpSerde {
     type Error = rmp_serde_1_3::decode::Error;
     /// Deserializes a type from bytes using the `rmp-serde` `1.3` crate.
     fn decode(data: Vec<u8>) -> Result<T, Self::Error> {
-        rmp_serde_1_3::decode::from_slice(&data)
+        Self::decode_slice(&data)
+    }
+
+    /// Deserializes a type from borrowed bytes using the `rmp-serde` `1.3` crate.
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        rmp_serde_1_3::decode::from_slice(data)
+    }
+
+    /// Deserializes a type from `reader` using the `rmp-serde` `1.3` crate.
+    ///
+    /// Fails once more than `LIMIT` bytes are read.
+    fn decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+    ) -> std::io::Result<T> {
+        rmp_serde_1_3::decode::from_read(reader.take(LIMIT as u64)).map_err(decode_io_error)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "rmp_serde_1_3"))]
+impl<'de, T: serde::Deserialize<'de>> crate::BorrowDecode<'de, T> for RmpSerde {
+    type Error = rmp_serde_1_3::decode::Error;
+    /// Deserializes a type borrowing from bytes using the `rmp-serde` `1.3` crate.
+    fn borrow_decode(data: &'de [u8]) -> Result<T, Self::Error> {
+        rmp_serde_1_3::decode::from_slice(data)
     }
 }
 
@@ -79,6 +114,18 @@ impl<T: serde::Serialize> crate::Encode<T> for RmpSerdeNamed {
     fn encode(obj: &T) -> Result<Vec<u8>, Self::Error> {
         rmp_serde_1_3::encode::to_vec_named(obj)
     }
+
+    /// Serializes a type at the end of `buffer` using the `rmp-serde` `1.3` crate.
+    fn encode_into(obj: &T, buffer: &mut Vec<u8>) -> Result<(), Self::Error> {
+        rmp_serde_1_3::encode::write_named(buffer, obj)
+    }
+
+    /// Serializes a type into `writer` using the `rmp-serde` `1.3` crate.
+    fn encode_to_writer<W: std::io::Write>(obj: &T, writer: W) -> std::io::Result<()> {
+        let mut writer = crate::codec::KeepIoError::new(writer);
+        let result = rmp_serde_1_3::encode::write_named(&mut writer, obj);
+        writer.into_result(result)
+    }
 }
 
 #[cfg(all(feature = "serde", feature = "rmp_serde_1_3"))]
@@ -86,6 +133,40 @@ impl<T: for<'de> serde::Deserialize<'de>> crate::Decode<T> for RmpSerdeNamed {
     type Error = rmp_serde_1_3::decode::Error;
     /// Deserializes a type from bytes using the `rmp-serde` `1.3` crate.
     fn decode(data: Vec<u8>) -> Result<T, Self::Error> {
-        rmp_serde_1_3::decode::from_slice(&data)
+        Self::decode_slice(&data)
+    }
+
+    /// Deserializes a type from borrowed bytes using the `rmp-serde` `1.3` crate.
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        rmp_serde_1_3::decode::from_slice(data)
+    }
+
+    /// Deserializes a type from `reader` using the `rmp-serde` `1.3` crate.
+    ///
+    /// Fails once more than `LIMIT` bytes are read.
+    fn decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+    ) -> std::io::Result<T> {
+        rmp_serde_1_3::decode::from_read(reader.take(LIMIT as u64)).map_err(decode_io_error)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "rmp_serde_1_3"))]
+impl<'de, T: serde::Deserialize<'de>> crate::BorrowDecode<'de, T> for RmpSerdeNamed {
+    type Error = rmp_serde_1_3::decode::Error;
+    /// Deserializes a type borrowing from bytes using the `rmp-serde` `1.3` crate.
+    fn borrow_decode(data: &'de [u8]) -> Result<T, Self::Error> {
+        rmp_serde_1_3::decode::from_slice(data)
+    }
+}
+
+/// Returns the error of the reader, or wraps the error of the deserializer.
+#[cfg(all(feature = "serde", feature = "rmp_serde_1_3"))]
+fn decode_io_error(error: rmp_serde_1_3::decode::Error) -> std::io::Error {
+    use rmp_serde_1_3::decode::Error;
+
+    match error {
+        Error::InvalidMarkerRead(e) | Error::InvalidDataRead(e) => e,
+        e => crate::codec::invalid_data_io_error(e),
     }
 }
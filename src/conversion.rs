@@ -0,0 +1,171 @@
+//! Support of the [`native_model`](crate::native_model) macro, not part of
+//! the public API.
+//!
+//! The `upgrade` and `downgrade` attributes only give the path of a
+//! function, the models it converts are inferred from its signature when it
+//! is passed to these functions.
+
+use crate::{DowngradeError, Model, Result, UpgradeError, UpgradeStep};
+use std::fmt::Display;
+
+/// Bound of the errors of the codec of generic models, the code generated
+/// for them can't rely on the error type of the codec implementation.
+pub trait CodecError:
+    Display + Into<anyhow::Error> + Into<Box<dyn std::error::Error + Send + Sync>>
+{
+}
+
+impl<E> CodecError for E where
+    E: Display + Into<anyhow::Error> + Into<Box<dyn std::error::Error + Send + Sync>>
+{
+}
+
+/// Expands the implementations of a model without `with`, which use the
+/// default codec, [`bincode_1_3::Bincode`](crate::bincode_1_3::Bincode).
+#[cfg(any(all(feature = "serde", feature = "bincode_1_3"), doc))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_default_codec {
+    ($($tokens:tt)*) => {
+        $($tokens)*
+    };
+}
+
+/// Fails to compile models without `with` when the feature of the default
+/// codec is disabled.
+#[cfg(not(any(all(feature = "serde", feature = "bincode_1_3"), doc)))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_default_codec {
+    ($($tokens:tt)*) => {
+        compile_error!(
+            "missing `with` attribute, the default codec `native_model::bincode_1_3::Bincode` \
+             requires the `bincode_1_3` feature of native_model: give the codec of the model, \
+             i.e. `with = native_model::postcard_1_0::PostCard`, or enable the `bincode_1_3` feature"
+        );
+    };
+}
+
+/// Implemented by `#[derive(NativeModel)]` for the structs it generates the
+/// conversions of, `from` of the derive requires the previous version to
+/// implement it, i.e. to derive `NativeModel` as well.
+pub trait DerivedNativeModel {
+    /// The fields kept in the next version.
+    type Next;
+
+    /// Fills the removed fields with their default.
+    fn from_next(next: Self::Next) -> Self;
+}
+
+/// The costs of upgrading the versions of the previous models to a model,
+/// keeping the shortest path of each version.
+///
+/// Each model computes its costs once from the costs of its previous models,
+/// shortcuts make the upgrades a graph which would be visited once per path
+/// otherwise.
+pub struct UpgradeCosts {
+    version: u32,
+    context: bool,
+    costs: Vec<(u32, u32)>,
+}
+
+impl UpgradeCosts {
+    /// The costs of the model `T`, counting the `upgrade_with` conversions
+    /// if `context`.
+    pub fn new<T: Model>(context: bool) -> Self {
+        Self {
+            version: T::VERSION,
+            context,
+            costs: vec![(T::VERSION, 0)],
+        }
+    }
+
+    /// Adds the paths through the conversion from `T`, if it is an older
+    /// version.
+    pub fn add<T: Model>(&mut self) {
+        if T::VERSION >= self.version {
+            return;
+        }
+        for &(version, cost) in T::native_model_upgrade_costs(self.context) {
+            match self.costs.iter_mut().find(|(known, _)| *known == version) {
+                Some((_, known_cost)) => *known_cost = (*known_cost).min(cost + 1),
+                None => self.costs.push((version, cost + 1)),
+            }
+        }
+    }
+
+    /// Adds the paths through the `upgrade_with` conversion from `T`.
+    pub fn add_with_context<T: Model>(&mut self) {
+        if self.context {
+            self.add::<T>();
+        }
+    }
+
+    /// Adds the paths through the `upgrade` function.
+    pub fn add_upgrade<Old: Model, New, E>(
+        &mut self,
+        _upgrade: impl FnOnce(Old) -> std::result::Result<New, E>,
+    ) {
+        self.add::<Old>();
+    }
+
+    pub fn into_costs(mut self) -> Vec<(u32, u32)> {
+        self.costs.sort_unstable();
+        self.costs
+    }
+}
+
+/// The cost of upgrading `version` to the model the `upgrade` function
+/// converts from.
+pub fn upgrade_cost<Old: Model, New: Model, E>(
+    _upgrade: impl FnOnce(Old) -> std::result::Result<New, E>,
+    version: u32,
+    context: bool,
+) -> Option<u32> {
+    if Old::VERSION < New::VERSION {
+        Old::native_model_upgrade_cost_with(version, context)
+    } else {
+        None
+    }
+}
+
+/// Decodes the model converted by `upgrade` with `decode`, then upgrades it.
+/// Returns the conversion along with the model, for the upgrade reports.
+pub fn decode_upgrade<Old: Model, New: Model, E>(
+    upgrade: impl FnOnce(Old) -> std::result::Result<New, E>,
+    decode: impl FnOnce() -> Result<Old>,
+) -> Result<(New, UpgradeStep)>
+where
+    E: Display + Into<anyhow::Error>,
+{
+    let old = decode()?;
+    let new = upgrade(old).map_err(upgrade_error)?;
+    let step = UpgradeStep::new::<Old, New>(
+        Old::native_model_version(),
+        New::native_model_version(),
+        true,
+    );
+    Ok((new, step))
+}
+
+pub fn encode_downgrade_body<Old: Model, New, E>(
+    downgrade: impl FnOnce(New) -> std::result::Result<Old, E>,
+    model: New,
+    version: u32,
+) -> Result<Vec<u8>>
+where
+    E: Display + Into<anyhow::Error>,
+{
+    let old = downgrade(model).map_err(|e| DowngradeError {
+        msg: format!("{}", e),
+        source: e.into(),
+    })?;
+    old.native_model_encode_downgrade_body(version)
+}
+
+fn upgrade_error<E: Display + Into<anyhow::Error>>(e: E) -> UpgradeError {
+    UpgradeError {
+        msg: format!("{}", e),
+        source: e.into(),
+    }
+}
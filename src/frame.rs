@@ -0,0 +1,224 @@
+//! Length-prefixed framing to store or send many models in a single stream.
+//!
+//! Each frame is the length of the encoded model, as a little-endian `u32`,
+//! followed by the encoded model (header and body). Frames can hold different
+//! models, they are told apart with [`Frame::id`].
+
+use crate::{BorrowModel, Error, Model, Result, Wrapper};
+use std::io::{self, Read, Write};
+
+/// Length of the prefix written before each frame.
+pub(crate) const LEN_SIZE: usize = 4;
+
+/// Length of the header of an encoded model.
+pub(crate) const HEADER_SIZE: usize = 8;
+
+/// Writes models as frames into `W`, i.e. a file or a socket.
+///
+/// Each model is encoded into a buffer reused between calls, then written
+/// with a single [`Write::write_all`].
+pub struct FrameWriter<W> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> FrameWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Encodes `model` and writes it as a frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FrameTooLarge`] if the encoded model doesn't fit in a
+    /// `u32` and [`Error::Io`] if the writer fails. Other errors depend on the
+    /// [`Encode`](crate::Encode) trait implementor (the serializer).
+    pub fn write<T: Model>(&mut self, model: &T) -> Result<()> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&[0; LEN_SIZE]);
+        model.native_model_encode_into(&mut self.buffer)?;
+        let len = self.buffer.len() - LEN_SIZE;
+        let len = u32::try_from(len).map_err(|_| Error::FrameTooLarge { len })?;
+        self.buffer[..LEN_SIZE].copy_from_slice(&len.to_le_bytes());
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads frames written by a [`FrameWriter`] from `R`.
+///
+/// Iterating over the reader yields every frame until the end of the stream,
+/// use [`FrameReader::read_frame_if`] to skip the frames of unknown models
+/// without reading their body into memory.
+pub struct FrameReader<R> {
+    reader: R,
+}
+
+impl<R: Read> FrameReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Reads the next frame, returns `None` at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidHeader`] if the frame is too short to contain
+    /// the header and [`Error::Io`] if the reader fails or the stream ends in
+    /// the middle of a frame.
+    pub fn read_frame(&mut self) -> Result<Option<Frame>> {
+        self.read_frame_if(|_, _| true)
+    }
+
+    /// Reads the next frame accepted by `accept`, called with the id and the
+    /// version of each frame. The body of the other frames is skipped.
+    ///
+    /// Returns `None` at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`FrameReader::read_frame`].
+    pub fn read_frame_if(
+        &mut self,
+        mut accept: impl FnMut(u32, u32) -> bool,
+    ) -> Result<Option<Frame>> {
+        loop {
+            let len = match self.read_len()? {
+                Some(len) => len,
+                None => return Ok(None),
+            };
+            if len < HEADER_SIZE as u64 {
+                return Err(Error::InvalidHeader);
+            }
+
+            let mut data = vec![0; HEADER_SIZE];
+            self.reader.read_exact(&mut data)?;
+            let header = Wrapper::deserialize(&data[..]).ok_or(Error::InvalidHeader)?;
+            let (id, version) = (header.get_id(), header.get_version());
+
+            let body_len = len - HEADER_SIZE as u64;
+            let mut body = (&mut self.reader).take(body_len);
+            if accept(id, version) {
+                if (body.read_to_end(&mut data)? as u64) < body_len {
+                    return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+                }
+                return Ok(Some(Frame { data }));
+            }
+            if io::copy(&mut body, &mut io::sink())? < body_len {
+                return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the length prefix, returns `None` if the stream ends before it.
+    fn read_len(&mut self) -> Result<Option<u64>> {
+        let mut len = [0; LEN_SIZE];
+        let mut filled = 0;
+        while filled < LEN_SIZE {
+            match self.reader.read(&mut len[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(Error::Io(io::ErrorKind::UnexpectedEof.into())),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(Some(u32::from_le_bytes(len).into()))
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// An encoded model read by a [`FrameReader`].
+#[derive(Debug, Clone)]
+pub struct Frame {
+    data: Vec<u8>,
+}
+
+impl Frame {
+    /// `data` must hold at least a header.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.wrapper().get_id()
+    }
+
+    pub fn version(&self) -> u32 {
+        self.wrapper().get_version()
+    }
+
+    /// Decodes the model, upgrading it if needed, and returns the version
+    /// ([`u32`]) it was encoded with.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`decode_slice`](crate::decode_slice).
+    pub fn decode<T: Model>(&self) -> Result<(T, u32)> {
+        crate::decode_slice(&self.data)
+    }
+
+    /// Decodes a model borrowing from the frame, see
+    /// [`decode_borrowed`](crate::decode_borrowed).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`decode_borrowed`](crate::decode_borrowed).
+    pub fn decode_borrowed<'de, T: BorrowModel<'de>>(&'de self) -> Result<(T, u32)> {
+        crate::decode_borrowed(&self.data)
+    }
+
+    /// Returns the encoded model, without the length prefix.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn wrapper(&self) -> Wrapper<&[u8]> {
+        Wrapper::deserialize(&self.data[..]).expect("frames hold at least a header")
+    }
+}
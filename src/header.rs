@@ -7,3 +7,17 @@ pub struct Header {
     pub(crate) id: U32,
     pub(crate) version: U32,
 }
+
+/// The header of an encoded model, returned by [`peek`](crate::peek).
+///
+/// New fields may be added if the header gets extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ModelHeader {
+    /// The id of the model, see [`Model::native_model_id`](crate::Model::native_model_id).
+    pub id: u32,
+    /// The version the model was encoded with.
+    pub version: u32,
+    /// The length of the body following the header.
+    pub body_len: usize,
+}
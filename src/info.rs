@@ -0,0 +1,30 @@
+/// Description of a [`Model`](crate::Model), available in constant
+/// expressions through [`Model::INFO`](crate::Model::INFO).
+///
+/// New fields may be added if the [`native_model`](crate::native_model)
+/// macro gets new attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct ModelInfo {
+    /// The id of the model, see [`Model::ID`](crate::Model::ID).
+    pub id: u32,
+    /// The version of the model, see [`Model::VERSION`](crate::Model::VERSION).
+    pub version: u32,
+    /// The name of the type of the model, without its module path.
+    pub type_name: &'static str,
+    /// The path of the [`Encode`](crate::Encode) and
+    /// [`Decode`](crate::Decode) implementor given to `with`, or of
+    /// [`bincode_1_3::Bincode`](crate::bincode_1_3::Bincode) without `with`.
+    pub codec: &'static str,
+}
+
+impl ModelInfo {
+    pub const fn new(id: u32, version: u32, type_name: &'static str, codec: &'static str) -> Self {
+        Self {
+            id,
+            version,
+            type_name,
+            codec,
+        }
+    }
+}
@@ -24,6 +24,240 @@ doc_comment! {
     include_str!("../README.md")
 }
 
+/// Variants of a [`NativeModelEnum`] sharing a model id don't compile.
+///
+/// ```compile_fail
+/// use native_model::{native_model, NativeModelEnum};
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 1)]
+/// struct A;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 2)]
+/// struct B;
+///
+/// #[derive(NativeModelEnum)]
+/// enum Message {
+///     A(A),
+///     B(B),
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelEnumDistinctIds;
+
+/// Models upgraded from a model with another id don't compile.
+///
+/// ```compile_fail
+/// use native_model::native_model;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 1)]
+/// struct Bar1;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 2, version = 2, from = Bar1)]
+/// struct Foo2;
+///
+/// impl From<Bar1> for Foo2 {
+///     fn from(_: Bar1) -> Self {
+///         Foo2
+///     }
+/// }
+///
+/// impl From<Foo2> for Bar1 {
+///     fn from(_: Foo2) -> Self {
+///         Bar1
+///     }
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelChainSameId;
+
+/// Models upgraded from a model without a lower version don't compile.
+///
+/// ```compile_fail
+/// use native_model::native_model;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 2)]
+/// struct Foo1;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 2, upgrade_from = [Foo1])]
+/// struct Foo2;
+///
+/// impl From<Foo1> for Foo2 {
+///     fn from(_: Foo1) -> Self {
+///         Foo2
+///     }
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelChainLowerVersion;
+
+/// Unknown attributes don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, verison = 1)]
+/// struct Foo;
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelUnknownAttribute;
+
+/// Models without id or version don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1)]
+/// struct Foo;
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelMissingVersion;
+
+/// Ids which aren't `u32` don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = -1, version = 1)]
+/// struct Foo;
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelU32Id;
+
+/// `try_from` with a single type doesn't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 1)]
+/// struct Foo1;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 2, try_from = (Foo1))]
+/// struct Foo2;
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelTryFromPair;
+
+/// `try_downgrade_to` with more than one pair doesn't compile, a model
+/// downgrades to a single previous version.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 1)]
+/// struct Foo1;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 2)]
+/// struct Foo2;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(
+///     id = 1,
+///     version = 3,
+///     try_downgrade_to = [(Foo1, std::convert::Infallible), (Foo2, std::convert::Infallible)]
+/// )]
+/// struct Foo3;
+///
+/// impl From<Foo3> for Foo1 {
+///     fn from(_: Foo3) -> Self {
+///         Foo1
+///     }
+/// }
+///
+/// impl From<Foo3> for Foo2 {
+///     fn from(_: Foo3) -> Self {
+///         Foo2
+///     }
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelTryDowngradeToSingle;
+
+/// Fields of the previous version missing from the next one without
+/// `removed_in` don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize, native_model::NativeModel)]
+/// #[native_model(id = 1, version = 1)]
+/// struct Foo1 {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// #[derive(serde::Deserialize, serde::Serialize, native_model::NativeModel)]
+/// #[native_model(id = 1, version = 2, from = Foo1)]
+/// struct Foo2 {
+///     x: u32,
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelDeriveRemovedField;
+
+/// Fields added after the version of the model don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize, native_model::NativeModel)]
+/// #[native_model(id = 1, version = 1)]
+/// struct Foo {
+///     #[native_model(added_in = 2)]
+///     x: u32,
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelDeriveAddedInLaterVersion;
+
+/// The previous version of a derived model must derive `NativeModel` as well.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 1)]
+/// struct Foo1 {
+///     x: u32,
+/// }
+///
+/// #[derive(serde::Deserialize, serde::Serialize, native_model::NativeModel)]
+/// #[native_model(id = 1, version = 2, from = Foo1)]
+/// struct Foo2 {
+///     x: u32,
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelDeriveFromAttribute;
+
+/// Histories with a model of another id don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 1)]
+/// struct Foo1;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 2, version = 2)]
+/// struct Foo2;
+///
+/// native_model::history! { id = 1; Foo1 => Foo2 }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelHistorySameId;
+
+/// Histories not in increasing versions don't compile.
+///
+/// ```compile_fail
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 1)]
+/// struct Foo1;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model::native_model(id = 1, version = 2)]
+/// struct Foo2;
+///
+/// native_model::history! { id = 1; Foo2 => Foo1 }
+/// ```
+#[cfg(doctest)]
+pub struct NativeModelHistoryIncreasingVersions;
+
 #[cfg(any(
     feature = "serde",
     feature = "bincode_1_3",
@@ -43,9 +277,23 @@ mod codec;
     doc
 ))]
 pub use codec::*;
+#[doc(hidden)]
+pub mod conversion;
+pub mod frame;
 mod header;
+mod info;
+pub mod registry;
+mod report;
+#[cfg(feature = "tokio")]
+pub mod tokio_codec;
+mod versions;
 pub mod wrapper;
 
+pub use header::ModelHeader;
+pub use info::ModelInfo;
+pub use report::{UpgradeReport, UpgradeStep};
+pub use versions::ModelVersions;
+
 // Macro to generate a [`native_model`] implementation for a struct.
 pub use native_model_macro::*;
 
@@ -75,6 +323,18 @@ pub enum Error {
     DowngradeNotSupported { from: u32, to: u32 },
     #[error("Wrong type id expected: {}, actual: {}", expected, actual)]
     WrongTypeId { expected: u32, actual: u32 },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Frame too large: {} bytes", len)]
+    FrameTooLarge { len: usize },
+    #[error("Model id {} is already registered", id)]
+    DuplicateModelId { id: u32 },
+    #[error("Unknown model id: {}", id)]
+    UnknownModelId { id: u32 },
+    #[error("Upgrade requires a context of type {}", context)]
+    MissingUpgradeContext { context: &'static str },
+    #[error("Unknown version {} of model id {}", version, id)]
+    UnknownModelVersion { id: u32, version: u32 },
 }
 
 pub type DecodeResult<T> = std::result::Result<T, DecodeBodyError>;
@@ -90,6 +350,10 @@ pub enum DecodeBodyError {
         #[source]
         source: anyhow::Error,
     },
+    /// The model has lifetime parameters, it can only be decoded by
+    /// [`decode_borrowed`].
+    #[error("Model borrowing from the bytes, decode it with decode_borrowed")]
+    BorrowedModel,
 }
 
 pub type EncodeResult<T> = std::result::Result<T, EncodeBodyError>;
@@ -132,6 +396,78 @@ pub fn encode<T: crate::Model>(model: &T) -> Result<Vec<u8>> {
     T::native_model_encode(model)
 }
 
+/// Allows to encode a [`native_model`] at the end of `buffer`.
+///
+/// The header and the body are written directly into `buffer`, which can be
+/// reused between calls. On error `buffer` is left as it was.
+///
+/// # Errors
+///
+/// The errors returned from this function depend on the [`Encode`] trait
+/// implementor (the serializer), i.e. `bincode_2`.
+pub fn encode_into<T: crate::Model>(model: &T, buffer: &mut Vec<u8>) -> Result<()> {
+    model.native_model_encode_into(buffer)
+}
+
+/// Allows to encode a [`native_model`] into `writer`, i.e. a file or a socket.
+///
+/// The body is streamed by the [`Encode`] implementor without being buffered.
+/// Wrap unbuffered writers in a [`std::io::BufWriter`], the serializers issue
+/// many small writes.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`] if `writer` fails, the errors of the [`Encode`] trait
+/// implementor (the serializer) are wrapped in it as well.
+pub fn encode_to_writer<T: crate::Model>(model: &T, writer: impl std::io::Write) -> Result<()> {
+    model.native_model_encode_to_writer(writer)
+}
+
+/// Reads the header of an encoded [`native_model`] without decoding the body.
+///
+/// Useful to route encoded models or to pick the type to decode them into
+/// without knowing the [`Decode`] implementor (the deserializer).
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidHeader`] if `data` is too short to contain the
+/// header.
+pub fn peek(data: &[u8]) -> Result<ModelHeader> {
+    let wrapper = Wrapper::deserialize(data).ok_or(Error::InvalidHeader)?;
+    Ok(ModelHeader {
+        id: wrapper.get_id(),
+        version: wrapper.get_version(),
+        body_len: wrapper.value().len(),
+    })
+}
+
+/// Upgrades an encoded [`native_model`] to the version of `T`, without
+/// going through the application types.
+///
+/// `data` is decoded into `T`, upgrading it if needed, then encoded again.
+/// It is returned untouched if it is already encoded with the version of
+/// `T`, which suits migrations rewriting stale records lazily.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidHeader`] if `data` is too short to contain the
+/// header and [`Error::WrongTypeId`] if it holds another model. Other errors
+/// are the ones of [`decode`] and [`encode`].
+pub fn upgrade_bytes<T: crate::Model>(data: &[u8]) -> Result<Vec<u8>> {
+    let header = peek(data)?;
+    if header.id != T::native_model_id() {
+        return Err(Error::WrongTypeId {
+            expected: T::native_model_id(),
+            actual: header.id,
+        });
+    }
+    if header.version == T::native_model_version() {
+        return Ok(data.to_vec());
+    }
+    let (model, _) = decode_slice::<T>(data)?;
+    encode(&model)
+}
+
 /// Allows to encode a [`native_model`] into a [`Vec<u8>`] with a specific version.
 /// See examples:
 ///    - [README.md](https://github.com/vincent-herlemont/native_model) file.
@@ -147,29 +483,306 @@ pub fn encode_downgrade<T: crate::Model>(model: T, version: u32) -> Result<Vec<u
 ///
 /// # Errors
 ///
-/// The errors returned from this function depend on the [`Decode`] trait
-/// implementor (the deserializer), i.e. `bincode_2`.
+/// Returns [`Error::InvalidHeader`] if `data` is too short to contain the
+/// header and [`DecodeBodyError::BorrowedModel`] if `T` has lifetime
+/// parameters, see [`decode_borrowed`]. Other errors depend on the [`Decode`]
+/// trait implementor (the deserializer), i.e. `bincode_2`.
 pub fn decode<T: crate::Model>(data: Vec<u8>) -> Result<(T, u32)> {
     T::native_model_decode(data)
 }
 
+/// Allows to decode a [`native_model`] from a borrowed slice and returns the version ([`u32`]).
+///
+/// The body is handed to the [`Decode`] implementor without being copied,
+/// which suits large values read from a memory mapped file or a shared buffer.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidHeader`] if `data` is too short to contain the
+/// header. Other errors depend on the [`Decode`] trait implementor (the
+/// deserializer), i.e. `bincode_2`.
+pub fn decode_slice<T: crate::Model>(data: &[u8]) -> Result<(T, u32)> {
+    T::native_model_decode(data)
+}
+
+/// Allows to decode a [`native_model`] from a borrowed slice and returns the
+/// conversions run to upgrade it, see [`UpgradeReport`].
+///
+/// # Errors
+///
+/// Same as [`decode_slice`].
+pub fn decode_with_report<T: crate::Model>(data: &[u8]) -> Result<(T, UpgradeReport)> {
+    T::native_model_decode_with_report(data)
+}
+
+/// Allows to decode a [`native_model`] from a borrowed slice, passing `context`
+/// to the [`UpgradeWith`] conversions, and returns the version ([`u32`]).
+///
+/// # Errors
+///
+/// Returns [`Error::MissingUpgradeContext`] if a conversion expects a context
+/// of another type than `C`, which is only checked at runtime. Other errors
+/// are the ones of [`decode_slice`].
+pub fn decode_with_context<T: crate::Model, C: std::any::Any>(
+    data: &[u8],
+    context: &C,
+) -> Result<(T, u32)> {
+    T::native_model_decode_with_context(data, context)
+}
+
+/// Allows to decode a model as it was stored, into the variant of the
+/// version it was encoded with, without upgrading it to the latest version.
+///
+/// `V` is the versions enum generated by [`history!`], i.e. to display
+/// exactly what was persisted.
+///
+/// # Errors
+///
+/// Returns [`Error::WrongTypeId`] if `data` has another model id and
+/// [`Error::UnknownModelVersion`] if its version isn't in the history. Other
+/// errors are the ones of [`decode_slice`].
+pub fn decode_as_stored<V: ModelVersions>(data: &[u8]) -> Result<V> {
+    V::native_model_decode_as_stored(data)
+}
+
+/// Allows to decode a [`native_model`] from `reader`, i.e. a file or a pipe, and returns the version ([`u32`]).
+///
+/// The header is read first, then the body is streamed into the [`Decode`]
+/// implementor which only reads the bytes of the model, see
+/// [`Decode::decode_from_reader`]. Wrap unbuffered readers in a
+/// [`std::io::BufReader`], the deserializers issue many small reads.
+///
+/// The deserializers read or allocate at most [`DEFAULT_READER_LIMIT`] bytes
+/// for the body, larger models are decoded with
+/// [`decode_from_reader_with_limit`].
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidHeader`] if `reader` ends before the end of the
+/// header and [`Error::Io`] if `reader` fails, the errors of the [`Decode`]
+/// trait implementor (the deserializer) and the limit being exceeded are
+/// wrapped in it as well.
+pub fn decode_from_reader<T: crate::Model>(reader: impl std::io::Read) -> Result<(T, u32)> {
+    T::native_model_decode_from_reader(reader)
+}
+
+/// Same as [`decode_from_reader`], the deserializers read or allocate at most
+/// `LIMIT` bytes for the body instead of [`DEFAULT_READER_LIMIT`].
+///
+/// ```rust
+/// # use native_model::native_model;
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 1)]
+/// struct Blob {
+///     data: Vec<u8>,
+/// }
+///
+/// let bytes = native_model::encode(&Blob { data: vec![0; 1024] }).unwrap();
+/// let result = native_model::decode_from_reader_with_limit::<Blob, 512>(&bytes[..]);
+/// assert!(result.is_err());
+/// let (blob, _) = native_model::decode_from_reader_with_limit::<Blob, 4096>(&bytes[..]).unwrap();
+/// assert_eq!(blob.data.len(), 1024);
+/// ```
+///
+/// # Errors
+///
+/// Same as [`decode_from_reader`].
+pub fn decode_from_reader_with_limit<T: crate::Model, const LIMIT: usize>(
+    reader: impl std::io::Read,
+) -> Result<(T, u32)> {
+    T::native_model_decode_from_reader_with_limit::<_, LIMIT>(reader)
+}
+
+/// The number of bytes the deserializers read or allocate at most for the
+/// body of a model decoded by [`decode_from_reader`], 256 MiB.
+///
+/// The length of a reader isn't known beforehand and the deserializers
+/// allocate the lengths read from the data before reading the bytes backing
+/// them, the limit bounds the allocation of a corrupted length.
+pub const DEFAULT_READER_LIMIT: usize = 1 << 28;
+
+/// Reads `reader` to the end, fails with an error of kind
+/// [`std::io::ErrorKind::InvalidData`] if it holds more than `LIMIT` bytes.
+pub(crate) fn read_to_end_with_limit<const LIMIT: usize>(
+    reader: impl std::io::Read,
+) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut data = Vec::new();
+    reader
+        .take((LIMIT as u64).saturating_add(1))
+        .read_to_end(&mut data)?;
+    if data.len() > LIMIT {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("more than {} bytes to decode", LIMIT),
+        ));
+    }
+    Ok(data)
+}
+
+/// Allows to decode a [`native_model`] borrowing from `data` and returns the version ([`u32`]).
+///
+/// Unlike [`decode`] the model may hold references to the body, i.e. `&'de str`
+/// or `Cow<'de, [u8]>` fields, which are then read without being allocated.
+/// The [`Decode`] implementor must implement [`BorrowDecode`].
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidHeader`] if `data` is too short to contain the
+/// header. Other errors depend on the [`BorrowDecode`] trait implementor (the
+/// deserializer), i.e. `bincode_2`.
+pub fn decode_borrowed<'de, T: crate::BorrowModel<'de>>(data: &'de [u8]) -> Result<(T, u32)> {
+    T::native_model_decode_borrowed(data)
+}
+
 pub trait Model: Sized {
+    /// The id of the model, usable in constant expressions.
+    const ID: u32;
+    /// The version of the model, usable in constant expressions.
+    const VERSION: u32;
+    /// The id, the version, the type name and the codec of the model.
+    const INFO: ModelInfo;
+
     fn native_model_id() -> u32;
     fn native_model_id_str() -> &'static str;
     fn native_model_version() -> u32;
     fn native_model_version_str() -> &'static str;
 
+    /// Returns the number of conversions of the shortest path upgrading
+    /// `version` to this version, or `None` if it can't be upgraded.
+    ///
+    /// The paths through `upgrade_with` conversions are counted, they only
+    /// apply to [`decode_with_context`].
+    fn native_model_upgrade_cost(version: u32) -> Option<u32> {
+        Self::native_model_upgrade_cost_with(version, true)
+    }
+
+    /// [`Model::native_model_upgrade_cost`], without the paths through
+    /// `upgrade_with` conversions unless `context`.
+    #[doc(hidden)]
+    fn native_model_upgrade_cost_with(version: u32, context: bool) -> Option<u32> {
+        let costs = Self::native_model_upgrade_costs(context);
+        costs
+            .binary_search_by_key(&version, |(version, _)| *version)
+            .ok()
+            .map(|index| costs[index].1)
+    }
+
+    /// The versions which can be upgraded to this version with the number of
+    /// conversions of their shortest path, sorted by version. Computed once
+    /// by the [`native_model`] macro, see [`conversion::UpgradeCosts`].
+    #[doc(hidden)]
+    fn native_model_upgrade_costs(context: bool) -> &'static [(u32, u32)];
+
+    /// Returns `true` if `data` holds an encoded model with the id of this
+    /// model, whatever its version. See [`peek`].
+    fn native_model_matches(data: &[u8]) -> bool {
+        matches!(crate::peek(data), Ok(header) if header.id == Self::native_model_id())
+    }
+
     // --------------- Decode ---------------
-    fn native_model_decode_body(data: Vec<u8>, id: u32) -> DecodeResult<Self>;
+    fn native_model_decode_body(data: &[u8], id: u32) -> DecodeResult<Self>;
 
-    fn native_model_decode_upgrade_body(data: Vec<u8>, id: u32, version: u32) -> Result<Self>;
+    fn native_model_decode_upgrade_body(data: &[u8], id: u32, version: u32) -> Result<Self>;
 
     fn native_model_decode(data: impl AsRef<[u8]>) -> Result<(Self, u32)> {
-        let native_model = crate::Wrapper::deserialize(data.as_ref()).unwrap();
+        let native_model =
+            crate::Wrapper::deserialize(data.as_ref()).ok_or(Error::InvalidHeader)?;
         let source_id = native_model.get_id();
         let source_version = native_model.get_version();
         let result = Self::native_model_decode_upgrade_body(
-            native_model.value().to_vec(),
+            native_model.value(),
+            source_id,
+            source_version,
+        )?;
+        Ok((result, source_version))
+    }
+
+    /// Same as [`Model::native_model_decode_upgrade_body`], records each
+    /// conversion into `report`.
+    fn native_model_decode_upgrade_body_with_report(
+        data: &[u8],
+        id: u32,
+        version: u32,
+        report: &mut UpgradeReport,
+    ) -> Result<Self>;
+
+    fn native_model_decode_with_report(data: impl AsRef<[u8]>) -> Result<(Self, UpgradeReport)> {
+        let native_model =
+            crate::Wrapper::deserialize(data.as_ref()).ok_or(Error::InvalidHeader)?;
+        let source_id = native_model.get_id();
+        let source_version = native_model.get_version();
+        let mut report = UpgradeReport::new(source_version, Self::native_model_version());
+        let result = Self::native_model_decode_upgrade_body_with_report(
+            native_model.value(),
+            source_id,
+            source_version,
+            &mut report,
+        )?;
+        Ok((result, report))
+    }
+
+    /// Same as [`Model::native_model_decode_upgrade_body`], passes `context`
+    /// to the [`UpgradeWith`] conversions.
+    fn native_model_decode_upgrade_body_with_context(
+        data: &[u8],
+        id: u32,
+        version: u32,
+        context: &dyn std::any::Any,
+    ) -> Result<Self>;
+
+    fn native_model_decode_with_context(
+        data: impl AsRef<[u8]>,
+        context: &dyn std::any::Any,
+    ) -> Result<(Self, u32)> {
+        let native_model =
+            crate::Wrapper::deserialize(data.as_ref()).ok_or(Error::InvalidHeader)?;
+        let source_id = native_model.get_id();
+        let source_version = native_model.get_version();
+        let result = Self::native_model_decode_upgrade_body_with_context(
+            native_model.value(),
+            source_id,
+            source_version,
+            context,
+        )?;
+        Ok((result, source_version))
+    }
+
+    fn native_model_decode_body_from_reader<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+        id: u32,
+    ) -> Result<Self> {
+        let data = crate::read_to_end_with_limit::<LIMIT>(reader)?;
+        Ok(Self::native_model_decode_body(&data, id)?)
+    }
+
+    fn native_model_decode_upgrade_body_from_reader<R: std::io::Read, const LIMIT: usize>(
+        reader: R,
+        id: u32,
+        version: u32,
+    ) -> Result<Self> {
+        let data = crate::read_to_end_with_limit::<LIMIT>(reader)?;
+        Self::native_model_decode_upgrade_body(&data, id, version)
+    }
+
+    fn native_model_decode_from_reader<R: std::io::Read>(reader: R) -> Result<(Self, u32)> {
+        Self::native_model_decode_from_reader_with_limit::<R, DEFAULT_READER_LIMIT>(reader)
+    }
+
+    fn native_model_decode_from_reader_with_limit<R: std::io::Read, const LIMIT: usize>(
+        mut reader: R,
+    ) -> Result<(Self, u32)> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header).map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => Error::InvalidHeader,
+            _ => Error::Io(e),
+        })?;
+        let native_model = crate::Wrapper::deserialize(&header[..]).ok_or(Error::InvalidHeader)?;
+        let source_id = native_model.get_id();
+        let source_version = native_model.get_version();
+        let result = Self::native_model_decode_upgrade_body_from_reader::<R, LIMIT>(
+            reader,
             source_id,
             source_version,
         )?;
@@ -180,16 +793,43 @@ pub trait Model: Sized {
 
     fn native_model_encode_body(&self) -> EncodeResult<Vec<u8>>;
 
+    fn native_model_encode_body_into(&self, buffer: &mut Vec<u8>) -> EncodeResult<()> {
+        buffer.extend_from_slice(&self.native_model_encode_body()?);
+        Ok(())
+    }
+
+    fn native_model_encode_body_to_writer<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(&self.native_model_encode_body()?)?;
+        Ok(())
+    }
+
     fn native_model_encode_downgrade_body(self, version: u32) -> Result<Vec<u8>>;
 
     fn native_model_encode(&self) -> Result<Vec<u8>> {
-        let mut data = self.native_model_encode_body()?;
-        let data = crate::native_model_encode(
-            &mut data,
+        let mut data = Vec::new();
+        self.native_model_encode_into(&mut data)?;
+        Ok(data)
+    }
+
+    fn native_model_encode_into(&self, buffer: &mut Vec<u8>) -> Result<()> {
+        let len = buffer.len();
+        buffer.extend_from_slice(&crate::native_model_encode_header(
             Self::native_model_id(),
             Self::native_model_version(),
-        );
-        Ok(data)
+        ));
+        if let Err(e) = self.native_model_encode_body_into(buffer) {
+            buffer.truncate(len);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    fn native_model_encode_to_writer<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(&crate::native_model_encode_header(
+            Self::native_model_id(),
+            Self::native_model_version(),
+        ))?;
+        self.native_model_encode_body_to_writer(writer)
     }
 
     fn native_model_encode_downgrade(self, version: u32) -> Result<Vec<u8>> {
@@ -198,3 +838,79 @@ pub trait Model: Sized {
         Ok(data)
     }
 }
+
+/// Decoding of a [`Model`] borrowing from bytes living for `'de`, used by
+/// [`decode_borrowed`] and implemented by the [`native_model`] macro.
+///
+/// Models with lifetime parameters only implement it for the `'de` outliving
+/// them, so they can borrow from the body. Other models implement it for
+/// every lifetime with their [`Model`] decode.
+pub trait BorrowModel<'de>: Model {
+    fn native_model_decode_borrowed_body(data: &'de [u8], id: u32) -> DecodeResult<Self>;
+
+    fn native_model_decode_upgrade_borrowed_body(
+        data: &'de [u8],
+        id: u32,
+        version: u32,
+    ) -> Result<Self>;
+
+    fn native_model_decode_borrowed(data: &'de [u8]) -> Result<(Self, u32)> {
+        let native_model = crate::Wrapper::deserialize(data).ok_or(Error::InvalidHeader)?;
+        let source_id = native_model.get_id();
+        let source_version = native_model.get_version();
+        let result = Self::native_model_decode_upgrade_borrowed_body(
+            native_model.into_value(),
+            source_id,
+            source_version,
+        )?;
+        Ok((result, source_version))
+    }
+}
+
+/// Upgrade from `Old` needing external state `C`, i.e. a default value or a
+/// lookup table, used by the `upgrade_with = (Old, C)` attribute of the
+/// [`native_model`] macro.
+///
+/// The context is passed to [`decode_with_context`], which hands it to the
+/// conversions as a [`std::any::Any`]. Decoding without it, or with a context
+/// of another type than `C`, returns [`Error::MissingUpgradeContext`] at
+/// runtime.
+///
+/// Example:
+/// ```rust
+/// # use native_model::{native_model, UpgradeWith};
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 1)]
+/// struct UserV1 {
+///     name: String,
+/// }
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 2, upgrade_with = (UserV1, Tenant))]
+/// struct UserV2 {
+///     name: String,
+///     tenant: u32,
+/// }
+///
+/// struct Tenant(u32);
+///
+/// impl UpgradeWith<UserV1, Tenant> for UserV2 {
+///     type Error = std::convert::Infallible;
+///
+///     fn upgrade_with(user: UserV1, tenant: &Tenant) -> Result<Self, Self::Error> {
+///         Ok(UserV2 {
+///             name: user.name,
+///             tenant: tenant.0,
+///         })
+///     }
+/// }
+///
+/// let bytes = native_model::encode(&UserV1 { name: "a".to_string() }).unwrap();
+/// let (user, _) = native_model::decode_with_context::<UserV2, _>(&bytes, &Tenant(7)).unwrap();
+/// assert_eq!(user.tenant, 7);
+/// ```
+pub trait UpgradeWith<Old, C: std::any::Any>: Sized {
+    type Error: std::fmt::Display + Into<anyhow::Error>;
+
+    fn upgrade_with(old: Old, context: &C) -> std::result::Result<Self, Self::Error>;
+}
@@ -0,0 +1,156 @@
+//! Decoding of models whose type is only known at runtime, from their id.
+
+use crate::{Error, Model, ModelVersions, Result};
+use std::any::Any;
+use std::collections::HashMap;
+
+/// A decoded model and the version ([`u32`]) it was encoded with, returned by
+/// the models added with [`ModelRegistry::register`].
+pub type AnyModel = (Box<dyn Any>, u32);
+
+type Handler<R> = Box<dyn Fn(&[u8]) -> Result<R> + Send + Sync>;
+
+/// Registry of models decoding encoded bytes into the model registered with
+/// their id, upgraded if needed.
+///
+/// Each model is either decoded into an [`AnyModel`], see
+/// [`ModelRegistry::register`], or handed to a callback returning `R`, see
+/// [`ModelRegistry::register_with`]. The versions of a model generated by
+/// [`history!`](crate::history) are decoded as they were stored instead, see
+/// [`ModelRegistry::register_as_stored`].
+///
+/// Example:
+/// ```rust
+/// # use native_model::*;
+/// # use native_model::registry::ModelRegistry;
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 1, version = 1)]
+/// struct Created(u32);
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// #[native_model(id = 2, version = 1)]
+/// struct Deleted(u32);
+///
+/// let mut registry = ModelRegistry::new();
+/// registry.register_with(|Created(id), _| format!("created {}", id)).unwrap();
+/// registry.register_with(|Deleted(id), _| format!("deleted {}", id)).unwrap();
+///
+/// let bytes = native_model::encode(&Deleted(7)).unwrap();
+/// assert_eq!(registry.decode(&bytes).unwrap(), "deleted 7");
+/// ```
+pub struct ModelRegistry<R = AnyModel> {
+    handlers: HashMap<u32, Handler<R>>,
+}
+
+impl<R> ModelRegistry<R> {
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Registers `T`, its models are decoded and passed to `handler` along
+    /// with the version they were encoded with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DuplicateModelId`] if a model with the same id is
+    /// already registered.
+    pub fn register_with<T: Model>(
+        &mut self,
+        handler: impl Fn(T, u32) -> R + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.insert(T::native_model_id(), move |data| {
+            let (model, version) = crate::decode_slice::<T>(data)?;
+            Ok(handler(model, version))
+        })
+    }
+
+    /// Registers the versions `V` of a model, its models are decoded as they
+    /// were stored, without upgrade, and passed to `handler`, see
+    /// [`decode_as_stored`](crate::decode_as_stored).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DuplicateModelId`] if a model with the same id is
+    /// already registered.
+    pub fn register_as_stored_with<V: ModelVersions>(
+        &mut self,
+        handler: impl Fn(V) -> R + Send + Sync + 'static,
+    ) -> Result<()> {
+        self.insert(V::ID, move |data| {
+            Ok(handler(crate::decode_as_stored::<V>(data)?))
+        })
+    }
+
+    fn insert(
+        &mut self,
+        id: u32,
+        handler: impl Fn(&[u8]) -> Result<R> + Send + Sync + 'static,
+    ) -> Result<()> {
+        if self.handlers.contains_key(&id) {
+            return Err(Error::DuplicateModelId { id });
+        }
+        self.handlers.insert(id, Box::new(handler));
+        Ok(())
+    }
+
+    /// Returns `true` if a model with the id `id` is registered.
+    pub fn contains(&self, id: u32) -> bool {
+        self.handlers.contains_key(&id)
+    }
+
+    /// Decodes `data` with the model registered with its id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidHeader`] if `data` is too short to contain the
+    /// header and [`Error::UnknownModelId`] if no model is registered with
+    /// its id. Other errors are the ones of [`decode`](crate::decode).
+    pub fn decode(&self, data: &[u8]) -> Result<R> {
+        let id = crate::peek(data)?.id;
+        let handler = self.handlers.get(&id).ok_or(Error::UnknownModelId { id })?;
+        handler(data)
+    }
+}
+
+impl ModelRegistry<AnyModel> {
+    /// Registers `T`, its models are decoded into a [`Box<dyn Any>`] to
+    /// downcast to `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DuplicateModelId`] if a model with the same id is
+    /// already registered.
+    pub fn register<T: Model + 'static>(&mut self) -> Result<()> {
+        self.register_with(|model: T, version| (Box::new(model) as Box<dyn Any>, version))
+    }
+
+    /// Registers the versions `V` of a model, its models are decoded as they
+    /// were stored into a [`Box<dyn Any>`] to downcast to `V`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DuplicateModelId`] if a model with the same id is
+    /// already registered.
+    pub fn register_as_stored<V: ModelVersions + 'static>(&mut self) -> Result<()> {
+        self.register_as_stored_with(|versions: V| {
+            let version = versions.native_model_version();
+            (Box::new(versions) as Box<dyn Any>, version)
+        })
+    }
+}
+
+impl<R> Default for ModelRegistry<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> std::fmt::Debug for ModelRegistry<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut ids = self.handlers.keys().collect::<Vec<_>>();
+        ids.sort();
+        f.debug_struct("ModelRegistry").field("ids", &ids).finish()
+    }
+}
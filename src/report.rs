@@ -0,0 +1,64 @@
+/// The conversions run to upgrade a decoded model, returned by
+/// [`decode_with_report`](crate::decode_with_report).
+///
+/// Allows to log migrations and to find out which records are worth being
+/// encoded again with the latest version, see
+/// [`upgrade_bytes`](crate::upgrade_bytes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UpgradeReport {
+    /// The version the model was encoded with.
+    pub source_version: u32,
+    /// The version of the decoded model.
+    pub target_version: u32,
+    /// The conversions, from the oldest version to the target version. Empty
+    /// if the model was encoded with the target version.
+    pub steps: Vec<UpgradeStep>,
+}
+
+impl UpgradeReport {
+    pub fn new(source_version: u32, target_version: u32) -> Self {
+        Self {
+            source_version,
+            target_version,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if at least one conversion ran.
+    pub fn is_upgraded(&self) -> bool {
+        !self.steps.is_empty()
+    }
+
+    /// Returns `true` if a conversion was a `try_from`, which may have
+    /// rejected the model.
+    pub fn is_fallible(&self) -> bool {
+        self.steps.iter().any(|step| step.fallible)
+    }
+}
+
+/// A conversion from a version of a model to the next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UpgradeStep {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// The name of the type converted from, see [`std::any::type_name`].
+    pub from_type: &'static str,
+    /// The name of the type converted to, see [`std::any::type_name`].
+    pub to_type: &'static str,
+    /// `true` for a `try_from` conversion, `false` for a `from` one.
+    pub fallible: bool,
+}
+
+impl UpgradeStep {
+    pub fn new<From, To>(from_version: u32, to_version: u32, fallible: bool) -> Self {
+        Self {
+            from_version,
+            to_version,
+            from_type: std::any::type_name::<From>(),
+            to_type: std::any::type_name::<To>(),
+            fallible,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! [`tokio_util::codec`] implementations to send and receive models over
+//! async streams, i.e. TCP or Unix sockets.
+//!
+//! Enable the `tokio` feature to use them with
+//! [`Framed`](tokio_util::codec::Framed),
+//! [`FramedRead`](tokio_util::codec::FramedRead) or
+//! [`FramedWrite`](tokio_util::codec::FramedWrite). The frames are the same as
+//! the ones of the [`frame`](crate::frame) module, so both sides don't have to
+//! be async.
+
+use crate::frame::{Frame, HEADER_SIZE, LEN_SIZE};
+use crate::{Error, Model, Result};
+use bytes::{Buf, BufMut, BytesMut};
+use std::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Default maximum length of a frame, protects the reader from allocating
+/// the length claimed by a corrupted or malicious prefix.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+/// Codec yielding raw [`Frame`]s, for streams carrying different models.
+///
+/// Encodes any [`Model`] and forwards raw [`Frame`]s as they are.
+#[derive(Debug, Clone)]
+pub struct FrameCodec {
+    max_frame_len: usize,
+}
+
+impl FrameCodec {
+    pub fn new() -> Self {
+        Self {
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+        }
+    }
+
+    /// Sets the maximum length of the frames read and written, defaults to
+    /// [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Writes a frame filled by `fill` at the end of `dst`, leaves `dst` as it
+    /// was on error.
+    fn encode_frame(
+        &self,
+        dst: &mut BytesMut,
+        fill: impl FnOnce(&mut BytesMut) -> Result<()>,
+    ) -> Result<()> {
+        let start = dst.len();
+        dst.put_u32_le(0);
+        let result = fill(dst).and_then(|()| {
+            let len = dst.len() - start - LEN_SIZE;
+            match u32::try_from(len) {
+                Ok(prefix) if len <= self.max_frame_len => Ok(prefix),
+                _ => Err(Error::FrameTooLarge { len }),
+            }
+        });
+        match result {
+            Ok(prefix) => {
+                dst[start..start + LEN_SIZE].copy_from_slice(&prefix.to_le_bytes());
+                Ok(())
+            }
+            Err(e) => {
+                dst.truncate(start);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Item = Frame;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Frame>> {
+        if src.len() < LEN_SIZE {
+            return Ok(None);
+        }
+        let mut prefix = [0; LEN_SIZE];
+        prefix.copy_from_slice(&src[..LEN_SIZE]);
+        let len = u32::from_le_bytes(prefix) as usize;
+        if len < HEADER_SIZE {
+            return Err(Error::InvalidHeader);
+        }
+        if len > self.max_frame_len {
+            return Err(Error::FrameTooLarge { len });
+        }
+        if src.len() < LEN_SIZE + len {
+            src.reserve(LEN_SIZE + len - src.len());
+            return Ok(None);
+        }
+        src.advance(LEN_SIZE);
+        Ok(Some(Frame::new(src.split_to(len).to_vec())))
+    }
+}
+
+impl<'a, T: Model> Encoder<&'a T> for FrameCodec {
+    type Error = Error;
+
+    fn encode(&mut self, model: &'a T, dst: &mut BytesMut) -> Result<()> {
+        self.encode_frame(dst, |dst| model.native_model_encode_to_writer(dst.writer()))
+    }
+}
+
+impl Encoder<Frame> for FrameCodec {
+    type Error = Error;
+
+    fn encode(&mut self, frame: Frame, dst: &mut BytesMut) -> Result<()> {
+        self.encode_frame(dst, |dst| {
+            dst.extend_from_slice(frame.as_bytes());
+            Ok(())
+        })
+    }
+}
+
+/// Codec yielding models of type `T`, upgraded if needed, along with the
+/// version ([`u32`]) they were encoded with.
+pub struct ModelCodec<T> {
+    frame_codec: FrameCodec,
+    model: PhantomData<fn() -> T>,
+}
+
+impl<T> ModelCodec<T> {
+    pub fn new() -> Self {
+        Self {
+            frame_codec: FrameCodec::new(),
+            model: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length of the frames read and written, defaults to
+    /// [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.frame_codec = self.frame_codec.max_frame_len(max_frame_len);
+        self
+    }
+}
+
+impl<T> Default for ModelCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ModelCodec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            frame_codec: self.frame_codec.clone(),
+            model: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for ModelCodec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelCodec")
+            .field("frame_codec", &self.frame_codec)
+            .finish()
+    }
+}
+
+impl<T: Model> Decoder for ModelCodec<T> {
+    type Item = (T, u32);
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<(T, u32)>> {
+        match self.frame_codec.decode(src)? {
+            Some(frame) => frame.decode().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a, T: Model> Encoder<&'a T> for ModelCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, model: &'a T, dst: &mut BytesMut) -> Result<()> {
+        self.frame_codec.encode(model, dst)
+    }
+}
+
+impl<T: Model> Encoder<T> for ModelCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, model: T, dst: &mut BytesMut) -> Result<()> {
+        self.frame_codec.encode(&model, dst)
+    }
+}
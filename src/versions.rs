@@ -0,0 +1,17 @@
+use crate::Result;
+
+/// The versions of a model, with one variant per version, generated by
+/// [`history!`](crate::history) for `FooVersions`.
+///
+/// Decoded with [`decode_as_stored`](crate::decode_as_stored) into the
+/// version the model was encoded with, without upgrading it.
+pub trait ModelVersions: Sized {
+    /// The id of the model.
+    const ID: u32;
+
+    /// Decodes `data` into the variant of the version it was encoded with.
+    fn native_model_decode_as_stored(data: &[u8]) -> Result<Self>;
+
+    /// The version of the model held by the variant.
+    fn native_model_version(&self) -> u32;
+}
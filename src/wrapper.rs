@@ -21,6 +21,12 @@ impl<T: SplitByteSlice> Wrapper<T> {
         &self.value
     }
 
+    /// Returns the body, keeping the lifetime of the packed bytes when `T` is a
+    /// borrowed slice.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
     pub fn get_type_id(&self) -> u32 {
         self.header.id.get()
     }
@@ -45,13 +51,20 @@ impl<T: SplitByteSliceMut> Wrapper<T> {
 }
 
 pub fn native_model_encode(data: &mut Vec<u8>, type_id: u32, version: u32) -> Vec<u8> {
+    let mut header = native_model_encode_header(type_id, version).to_vec();
+    header.append(data);
+    header
+}
+
+/// Returns the header bytes written before the body of a model.
+pub fn native_model_encode_header(type_id: u32, version: u32) -> [u8; 8] {
     let header = Header {
         id: U32::new(type_id),
         version: U32::new(version),
     };
-    let mut header = header.as_bytes().to_vec();
-    header.append(data);
-    header
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(header.as_bytes());
+    bytes
 }
 
 #[cfg(test)]
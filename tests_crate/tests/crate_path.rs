@@ -0,0 +1,110 @@
+#![cfg(feature = "bincode_1_3")]
+
+// Shadows the `native_model` crate, the generated code must only use the
+// path given to `crate`.
+#[allow(dead_code)]
+mod native_model {}
+
+mod platform {
+    pub use ::native_model;
+}
+
+use platform::native_model::{native_model, Model, NativeModelEnum};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1, crate = platform::native_model)]
+struct FooV1 {
+    x: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = FooV1, crate = crate::platform::native_model)]
+struct FooV2 {
+    x: u32,
+    y: u32,
+}
+
+impl From<FooV1> for FooV2 {
+    fn from(old: FooV1) -> Self {
+        FooV2 { x: old.x, y: 0 }
+    }
+}
+
+impl From<FooV2> for FooV1 {
+    fn from(new: FooV2) -> Self {
+        FooV1 { x: new.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(
+    id = 2,
+    version = 1,
+    with = platform::native_model::bincode_1_3::Bincode,
+    crate = platform::native_model
+)]
+struct Bar<'a> {
+    name: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 1, crate = platform::native_model)]
+struct Page<T> {
+    items: Vec<T>,
+}
+
+#[derive(Debug, PartialEq, NativeModelEnum)]
+#[native_model(crate = platform::native_model)]
+enum Message {
+    Foo(FooV2),
+    Page(Page<u32>),
+}
+
+#[test]
+fn encode_decode_with_crate_path() {
+    let foo = FooV1 { x: 1 };
+    let encoded = platform::native_model::encode(&foo).unwrap();
+    let (decoded, version) = platform::native_model::decode::<FooV2>(encoded).unwrap();
+    assert_eq!(decoded, FooV2 { x: 1, y: 0 });
+    assert_eq!(version, 1);
+    assert_eq!(FooV2::VERSION, 2);
+
+    let encoded = platform::native_model::encode_downgrade(decoded, 1).unwrap();
+    let (decoded, _) = platform::native_model::decode::<FooV1>(encoded).unwrap();
+    assert_eq!(decoded, foo);
+}
+
+#[test]
+fn borrowed_and_generic_models_with_crate_path() {
+    let bar = Bar { name: "bar" };
+    let encoded = platform::native_model::encode(&bar).unwrap();
+    let (decoded, _) = platform::native_model::decode_borrowed::<Bar>(&encoded).unwrap();
+    assert_eq!(decoded, bar);
+
+    let page = Page { items: vec![1u32] };
+    let encoded = platform::native_model::encode(&page).unwrap();
+    let (decoded, _) = platform::native_model::decode::<Page<u32>>(encoded).unwrap();
+    assert_eq!(decoded, page);
+}
+
+#[test]
+fn model_enum_with_crate_path() {
+    let encoded = platform::native_model::encode(&FooV1 { x: 1 }).unwrap();
+    let (decoded, version) = Message::native_model_decode(&encoded).unwrap();
+    assert_eq!(decoded, Message::Foo(FooV2 { x: 1, y: 0 }));
+    assert_eq!(version, 1);
+
+    let page = Message::Page(Page { items: vec![1] });
+    let encoded = page.native_model_encode().unwrap();
+    let (decoded, _) = Message::native_model_decode(encoded).unwrap();
+    assert_eq!(decoded, page);
+}
+
+#[test]
+fn default_codec_with_crate_path() {
+    assert_eq!(
+        FooV1::INFO.codec,
+        "platform::native_model::bincode_1_3::Bincode"
+    );
+}
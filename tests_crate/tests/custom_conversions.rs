@@ -0,0 +1,108 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::native_model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(
+    id = 1,
+    version = 2,
+    upgrade = migrations::foo1_to_foo2,
+    downgrade = migrations::foo2_to_foo1
+)]
+struct Foo2 {
+    x: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3, upgrade = migrations::foo2_to_foo3)]
+struct Foo3 {
+    x: u64,
+}
+
+// Helpers which aren't `From` or `TryFrom` implementations.
+mod migrations {
+    use super::*;
+    use std::convert::Infallible;
+
+    pub fn foo1_to_foo2(foo1: Foo1) -> Result<Foo2, Infallible> {
+        Ok(Foo2 { x: foo1.x.into() })
+    }
+
+    pub fn foo2_to_foo1(foo2: Foo2) -> Result<Foo1, anyhow::Error> {
+        Ok(Foo1 {
+            x: foo2.x.try_into()?,
+        })
+    }
+
+    pub fn foo2_to_foo3(foo2: Foo2) -> Result<Foo3, anyhow::Error> {
+        Ok(Foo3 {
+            x: foo2.x.try_into()?,
+        })
+    }
+}
+
+#[test]
+fn custom_upgrade() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (foo2, version) = native_model::decode::<Foo2>(foo1_packed.clone()).unwrap();
+    assert_eq!(foo2, Foo2 { x: 1 });
+    assert_eq!(version, 1);
+
+    let (foo3, version) = native_model::decode::<Foo3>(foo1_packed.clone()).unwrap();
+    assert_eq!(foo3, Foo3 { x: 1 });
+    assert_eq!(version, 1);
+
+    let (foo3, _) = native_model::decode_from_reader::<Foo3>(&foo1_packed[..]).unwrap();
+    assert_eq!(foo3, Foo3 { x: 1 });
+
+    let (_, report) = native_model::decode_with_report::<Foo3>(&foo1_packed).unwrap();
+    let steps = report
+        .steps
+        .iter()
+        .map(|step| (step.from_version, step.to_version))
+        .collect::<Vec<_>>();
+    assert_eq!(steps, vec![(1, 2), (2, 3)]);
+    assert!(report.steps[1].from_type.ends_with("Foo2"));
+    assert!(report.steps[1].to_type.ends_with("Foo3"));
+
+    use native_model::Model;
+    assert_eq!(Foo3::native_model_upgrade_cost(1), Some(2));
+    assert_eq!(Foo3::native_model_upgrade_cost(0), None);
+}
+
+#[test]
+fn custom_upgrade_error() {
+    let foo1_packed = native_model::encode(&Foo1 { x: -1 }).unwrap();
+    let result = native_model::decode::<Foo3>(foo1_packed.clone());
+    assert!(matches!(result, Err(native_model::Error::UpgradeError(_))));
+    let result = native_model::decode_from_reader::<Foo3>(&foo1_packed[..]);
+    assert!(matches!(result, Err(native_model::Error::UpgradeError(_))));
+}
+
+#[test]
+fn custom_downgrade() {
+    let foo1_packed = native_model::encode_downgrade(Foo2 { x: 2 }, 1).unwrap();
+    let (foo1, version) = native_model::decode::<Foo1>(foo1_packed).unwrap();
+    assert_eq!(foo1, Foo1 { x: 2 });
+    assert_eq!(version, 1);
+
+    let result = native_model::encode_downgrade(Foo2 { x: i64::MAX }, 1);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DowngradeError(_))
+    ));
+
+    // `Foo3` only has an upgrade function.
+    let result = native_model::encode_downgrade(Foo3 { x: 3 }, 2);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DowngradeNotSupported { from: 2, to: 3 })
+    ));
+}
@@ -0,0 +1,335 @@
+//! Feeds arbitrary bytes to every public decode entry point: they must return
+//! an error instead of panicking.
+#![cfg(feature = "serde")]
+
+use native_model::wrapper::Wrapper;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Xorshift generator, keeps the inputs reproducible without a fuzzing harness.
+struct Random(u64);
+
+impl Random {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn bytes(&mut self, max_len: u64) -> Vec<u8> {
+        let len = self.next_u64() % max_len;
+        (0..len).map(|_| self.next_u64() as u8).collect()
+    }
+}
+
+/// Builds the corpus from a valid encoded model: every truncation, every
+/// single byte corruption, random bodies behind the valid header and random
+/// buffers.
+fn corpus(valid: &[u8], random: &mut Random) -> Vec<Vec<u8>> {
+    let mut corpus = vec![];
+    for len in 0..valid.len() {
+        corpus.push(valid[..len].to_vec());
+    }
+    for i in 0..valid.len() {
+        for value in [0x00, 0x7f, 0x80, 0xfb, 0xfc, 0xfd, 0xff] {
+            let mut bytes = valid.to_vec();
+            bytes[i] = value;
+            corpus.push(bytes);
+        }
+    }
+    for _ in 0..2000 {
+        let mut bytes = valid[..8].to_vec();
+        bytes.extend(random.bytes(64));
+        corpus.push(bytes);
+        corpus.push(random.bytes(64));
+    }
+    corpus
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+enum Kind {
+    Empty,
+    Value(u128),
+    Pair { a: i8, b: char },
+}
+
+#[cfg(feature = "bincode_1_3")]
+#[test]
+fn decode_truncated_header() {
+    #[derive(Debug, Serialize, Deserialize)]
+    #[native_model::native_model(id = 1, version = 1)]
+    struct Foo {
+        x: u32,
+    }
+
+    for len in 0..8 {
+        let result = native_model::decode::<Foo>(vec![0; len]);
+        assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+    }
+}
+
+#[test]
+fn wrapper_deserialize_arbitrary_bytes() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    for _ in 0..10_000 {
+        let bytes = random.bytes(32);
+        match Wrapper::deserialize(&bytes[..]) {
+            Some(wrapper) => assert_eq!(wrapper.value().len(), bytes.len() - 8),
+            None => assert!(bytes.len() < 8),
+        }
+    }
+}
+
+macro_rules! decode_arbitrary_bytes {
+    ($name:ident, $feature:literal, $with:path) => {
+        #[cfg(feature = $feature)]
+        mod $name {
+            use super::*;
+            use native_model::frame::FrameReader;
+            use native_model::registry::ModelRegistry;
+            use native_model::{native_model, Decode, Model, NativeModelEnum};
+            use std::borrow::Cow;
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 1, version = 1, with = $with)]
+            struct Record1 {
+                name: String,
+                data: Vec<u8>,
+                tags: Vec<String>,
+                kind: Kind,
+                extra: Option<u64>,
+                map: HashMap<String, Vec<u32>>,
+            }
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 1, version = 2, with = $with, try_from = (Record1, anyhow::Error))]
+            struct Record2 {
+                name: String,
+                kind: Kind,
+            }
+
+            impl TryFrom<Record1> for Record2 {
+                type Error = anyhow::Error;
+
+                fn try_from(record: Record1) -> Result<Self, Self::Error> {
+                    if record.name.is_empty() {
+                        return Err(anyhow::anyhow!("empty name"));
+                    }
+                    Ok(Record2 {
+                        name: record.name,
+                        kind: record.kind,
+                    })
+                }
+            }
+
+            impl TryFrom<Record2> for Record1 {
+                type Error = anyhow::Error;
+
+                fn try_from(record: Record2) -> Result<Self, Self::Error> {
+                    Ok(Record1 {
+                        name: record.name,
+                        data: vec![],
+                        tags: vec![],
+                        kind: record.kind,
+                        extra: None,
+                        map: HashMap::new(),
+                    })
+                }
+            }
+
+            native_model::history! { id = 1; Record1 => Record2 }
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 1, version = 1, with = $with)]
+            struct RecordRef<'a> {
+                name: &'a str,
+                #[serde(borrow)]
+                data: Cow<'a, [u8]>,
+            }
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 2, version = 1, with = $with)]
+            struct Note {
+                text: String,
+            }
+
+            #[derive(Debug, PartialEq, NativeModelEnum)]
+            enum Message {
+                Record(Record2),
+                Note(Note),
+            }
+
+            fn valid() -> Vec<u8> {
+                let record = Record1 {
+                    name: "name".to_string(),
+                    data: vec![1, 2, 3],
+                    tags: vec!["a".to_string(), "b".to_string()],
+                    kind: Kind::Pair { a: -1, b: 'é' },
+                    extra: Some(u64::MAX),
+                    map: HashMap::from([("k".to_string(), vec![1, 300, 70000])]),
+                };
+                native_model::encode(&record).unwrap()
+            }
+
+            #[test]
+            fn decode_arbitrary_bytes() {
+                let mut random = Random(0x9e37_79b9_7f4a_7c15);
+                let valid = valid();
+                assert!(native_model::decode::<Record2>(valid.clone()).is_ok());
+                let mut registry = ModelRegistry::new();
+                registry.register::<Record2>().unwrap();
+                registry.register::<Note>().unwrap();
+                let mut stored_registry = ModelRegistry::new();
+                stored_registry
+                    .register_as_stored::<RecordVersions>()
+                    .unwrap();
+
+                for bytes in corpus(&valid, &mut random) {
+                    let _ = native_model::decode::<Record1>(bytes.clone());
+                    let _ = native_model::decode::<Record2>(bytes.clone());
+                    let _ = native_model::decode_slice::<Record2>(&bytes);
+                    let _ = Record2::native_model_decode(&bytes);
+                    let _ = native_model::decode_borrowed::<RecordRef>(&bytes);
+                    let _ = native_model::decode_borrowed::<Record2>(&bytes);
+                    let _ = native_model::decode_with_report::<Record2>(&bytes);
+                    let _ = native_model::decode_with_context::<Record2, _>(&bytes, &());
+                    let _ = match native_model::decode_as_stored(&bytes) {
+                        Ok(RecordVersions::Record1(record)) => Some(record.name),
+                        Ok(RecordVersions::Record2(record)) => Some(record.name),
+                        Err(_) => None,
+                    };
+                    let _ = Message::native_model_decode(&bytes);
+                    let _ = registry.decode(&bytes);
+                    let _ = stored_registry.decode(&bytes);
+
+                    let body = bytes.get(8..).unwrap_or(&bytes);
+                    let id = random.next_u32() % 3;
+                    let version = [0, 1, 2, 3, random.next_u32()][random.next_u32() as usize % 5];
+                    let _ = Record1::native_model_decode_body(body, id);
+                    let _ = Record2::native_model_decode_body(body, id);
+                    let _ = Record1::native_model_decode_upgrade_body(body, id, version);
+                    let _ = Record2::native_model_decode_upgrade_body(body, id, version);
+
+                    let _ = <$with as Decode<Record1>>::decode_slice(&bytes);
+                    let _ = <$with as Decode<Record2>>::decode_slice(&bytes);
+                    let _ = <$with as Decode<Record1>>::decode(bytes.clone());
+                    let _ = <$with as Decode<Record2>>::decode(bytes);
+                }
+            }
+
+            /// Prefixes `bytes` with a length, either the length of `bytes` or
+            /// a random one.
+            fn frame(bytes: &[u8], random: &mut Random) -> Vec<u8> {
+                let len = match random.next_u32() % 3 {
+                    0 => random.next_u32(),
+                    _ => bytes.len() as u32,
+                };
+                let mut frame = len.to_le_bytes().to_vec();
+                frame.extend(bytes);
+                frame
+            }
+
+            #[test]
+            fn frame_reader_arbitrary_bytes() {
+                let mut random = Random(0x8cb9_2ba7_2f3d_8dd7);
+                let valid = valid();
+
+                for bytes in corpus(&valid, &mut random) {
+                    let stream = frame(&bytes, &mut random);
+                    for frame in FrameReader::new(&stream[..]).take(2) {
+                        let Ok(frame) = frame else { break };
+                        let _ = frame.decode::<Record2>();
+                        let _ = frame.decode_borrowed::<RecordRef>();
+                    }
+                    for frame in FrameReader::new(&bytes[..]).take(2) {
+                        let Ok(frame) = frame else { break };
+                        let _ = frame.decode::<Record1>();
+                    }
+                }
+            }
+
+            #[cfg(feature = "tokio")]
+            #[test]
+            fn tokio_codec_arbitrary_bytes() {
+                use native_model::tokio_codec::{FrameCodec, ModelCodec};
+                use tokio_util::codec::Decoder;
+
+                let mut random = Random(0x6a09_e667_f3bc_c909);
+                let valid = valid();
+
+                for bytes in corpus(&valid, &mut random) {
+                    let stream = frame(&bytes, &mut random);
+                    let mut src = stream[..].into();
+                    if let Ok(Some(frame)) = FrameCodec::new().decode(&mut src) {
+                        let _ = frame.decode::<Record2>();
+                    }
+                    let mut src = stream[..].into();
+                    let _ = ModelCodec::<Record2>::new().decode(&mut src);
+                    let mut src = bytes[..].into();
+                    let _ = ModelCodec::<Record1>::new().decode_eof(&mut src);
+                }
+            }
+
+            #[test]
+            fn decode_large_input_huge_length() {
+                // A length prefix of about 2^40 for the name with bincode, at
+                // the start of a body of 17 MiB.
+                let mut bytes = valid()[..8].to_vec();
+                bytes.extend([0xfd, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00]);
+                bytes.resize(8 + (17 << 20), 0);
+
+                let _ = native_model::decode_slice::<Record1>(&bytes);
+                let _ = <$with as Decode<Record1>>::decode_slice(&bytes[8..]);
+            }
+
+            #[test]
+            fn decode_from_reader_arbitrary_bytes() {
+                let mut random = Random(0xd1b5_4a32_d192_ed03);
+                let valid = valid();
+                assert!(native_model::decode_from_reader::<Record2>(&valid[..]).is_ok());
+
+                for bytes in corpus(&valid, &mut random) {
+                    let _ = native_model::decode_from_reader::<Record1>(&bytes[..]);
+                    let _ = native_model::decode_from_reader::<Record2>(&bytes[..]);
+
+                    let body = bytes.get(8..).unwrap_or(&bytes);
+                    let _ = <$with as Decode<Record1>>::decode_from_reader(body);
+                    let _ = <$with as Decode<Record2>>::decode_from_reader(body);
+                }
+            }
+
+            #[test]
+            fn decode_from_reader_huge_length() {
+                // A length prefix of about 2^60 for the name, behind the valid
+                // header.
+                let mut bytes = valid()[..8].to_vec();
+                bytes.extend([0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+
+                assert!(native_model::decode_from_reader::<Record1>(&bytes[..]).is_err());
+                assert!(<$with as Decode<Record1>>::decode_from_reader(&bytes[8..]).is_err());
+            }
+        }
+    };
+}
+
+decode_arbitrary_bytes!(
+    bincode_1_3,
+    "bincode_1_3",
+    native_model::bincode_1_3::Bincode
+);
+decode_arbitrary_bytes!(bincode_2, "bincode_2", native_model::bincode_2::Bincode);
+decode_arbitrary_bytes!(
+    postcard_1_0,
+    "postcard_1_0",
+    native_model::postcard_1_0::PostCard
+);
+decode_arbitrary_bytes!(
+    rmp_serde_1_3,
+    "rmp_serde_1_3",
+    native_model::rmp_serde_1_3::RmpSerde
+);
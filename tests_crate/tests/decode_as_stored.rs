@@ -0,0 +1,115 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::registry::ModelRegistry;
+use native_model::{native_model, ModelVersions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Order1 {
+    amount: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Order1)]
+struct Order2 {
+    amount: u64,
+    currency: String,
+}
+
+impl From<Order1> for Order2 {
+    fn from(order: Order1) -> Self {
+        Order2 {
+            amount: order.amount as u64,
+            currency: "EUR".to_string(),
+        }
+    }
+}
+
+impl From<Order2> for Order1 {
+    fn from(order: Order2) -> Self {
+        Order1 {
+            amount: order.amount as u32,
+        }
+    }
+}
+
+native_model::history! { id = 1; Order1 => Order2 }
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Refund {
+    amount: u32,
+}
+
+native_model::history! { id = 2; Refund }
+
+fn describe(order: OrderVersions) -> String {
+    match order {
+        OrderVersions::Order1(order) => format!("v1 {}", order.amount),
+        OrderVersions::Order2(order) => format!("v2 {} {}", order.amount, order.currency),
+    }
+}
+
+#[test]
+fn decode_as_stored() {
+    let encoded = native_model::encode(&Order1 { amount: 5 }).unwrap();
+    let order: OrderVersions = native_model::decode_as_stored(&encoded).unwrap();
+    assert_eq!(order.native_model_version(), 1);
+    assert_eq!(describe(order), "v1 5");
+
+    // Decoding upgrades the same bytes.
+    let (order, version) = native_model::decode::<OrderLatest>(encoded).unwrap();
+    assert_eq!(order.currency, "EUR");
+    assert_eq!(version, 1);
+
+    let encoded = native_model::encode(&Order2 {
+        amount: 7,
+        currency: "USD".to_string(),
+    })
+    .unwrap();
+    let order: OrderVersions = native_model::decode_as_stored(&encoded).unwrap();
+    assert_eq!(describe(order), "v2 7 USD");
+    assert_eq!(<OrderVersions as ModelVersions>::ID, 1);
+}
+
+#[test]
+fn decode_as_stored_wrong_id() {
+    let encoded = native_model::encode(&Refund { amount: 1 }).unwrap();
+    let result = native_model::decode_as_stored::<OrderVersions>(&encoded);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::WrongTypeId {
+            expected: 1,
+            actual: 2
+        })
+    ));
+}
+
+#[test]
+fn registry_as_stored() {
+    let mut registry = ModelRegistry::new();
+    registry.register_as_stored_with(describe).unwrap();
+    registry
+        .register_as_stored_with(|RefundVersions::Refund(refund)| {
+            format!("refund {}", refund.amount)
+        })
+        .unwrap();
+    assert!(registry
+        .register_as_stored_with(|_: OrderVersions| String::new())
+        .is_err());
+
+    let order = native_model::encode(&Order1 { amount: 3 }).unwrap();
+    let refund = native_model::encode(&Refund { amount: 4 }).unwrap();
+    assert_eq!(registry.decode(&order).unwrap(), "v1 3");
+    assert_eq!(registry.decode(&refund).unwrap(), "refund 4");
+
+    let mut registry = ModelRegistry::new();
+    registry.register_as_stored::<OrderVersions>().unwrap();
+    let (order, version) = registry.decode(&order).unwrap();
+    assert_eq!(version, 1);
+    assert!(matches!(
+        order.downcast_ref::<OrderVersions>(),
+        Some(OrderVersions::Order1(Order1 { amount: 3 }))
+    ));
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::native_model;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Record1<'a> {
+    name: &'a str,
+    #[serde(borrow)]
+    data: Cow<'a, [u8]>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Record1<'a>)]
+struct Record2<'a> {
+    name: &'a str,
+    #[serde(borrow)]
+    data: Cow<'a, [u8]>,
+    size: usize,
+}
+
+impl<'a> From<Record1<'a>> for Record2<'a> {
+    fn from(record: Record1<'a>) -> Self {
+        Record2 {
+            name: record.name,
+            size: record.data.len(),
+            data: record.data,
+        }
+    }
+}
+
+impl<'a> From<Record2<'a>> for Record1<'a> {
+    fn from(record: Record2<'a>) -> Self {
+        Record1 {
+            name: record.name,
+            data: record.data,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Owned {
+    name: String,
+}
+
+fn borrows_from(value: &[u8], buffer: &[u8]) -> bool {
+    buffer.as_ptr_range().contains(&value.as_ptr())
+}
+
+#[test]
+fn decode_borrowed() {
+    let record = Record2 {
+        name: "name",
+        data: Cow::Borrowed(&[1, 2, 3]),
+        size: 3,
+    };
+    let record_packed = native_model::encode(&record).unwrap();
+    let (record_decoded, version) =
+        native_model::decode_borrowed::<Record2>(&record_packed).unwrap();
+    assert_eq!(record, record_decoded);
+    assert_eq!(version, 2);
+    assert!(borrows_from(record_decoded.name.as_bytes(), &record_packed));
+    assert!(
+        matches!(record_decoded.data, Cow::Borrowed(data) if borrows_from(data, &record_packed))
+    );
+}
+
+#[test]
+fn decode_borrowed_upgrade() {
+    let record = Record1 {
+        name: "name",
+        data: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let record_packed = native_model::encode(&record).unwrap();
+    let (record_decoded, version) =
+        native_model::decode_borrowed::<Record2>(&record_packed).unwrap();
+    assert_eq!(
+        Record2 {
+            name: "name",
+            data: Cow::Borrowed(&[1, 2, 3]),
+            size: 3,
+        },
+        record_decoded
+    );
+    assert_eq!(version, 1);
+    assert!(borrows_from(record_decoded.name.as_bytes(), &record_packed));
+}
+
+#[test]
+fn decode_borrowed_downgrade() {
+    let record = Record2 {
+        name: "name",
+        data: Cow::Borrowed(&[1, 2, 3]),
+        size: 3,
+    };
+    let record_packed = native_model::encode_downgrade(record, 1).unwrap();
+    let (record_decoded, version) =
+        native_model::decode_borrowed::<Record1>(&record_packed).unwrap();
+    assert_eq!(
+        Record1 {
+            name: "name",
+            data: Cow::Borrowed(&[1, 2, 3]),
+        },
+        record_decoded
+    );
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn decode_borrowed_owned_model() {
+    let owned = Owned {
+        name: "name".to_string(),
+    };
+    let owned_packed = native_model::encode(&owned).unwrap();
+    let (owned_decoded, _) = native_model::decode_borrowed::<Owned>(&owned_packed).unwrap();
+    assert_eq!(owned, owned_decoded);
+    let (owned_decoded, _) = native_model::decode::<Owned>(owned_packed).unwrap();
+    assert_eq!(owned, owned_decoded);
+}
+
+#[test]
+fn decode_borrowed_invalid_header() {
+    let result = native_model::decode_borrowed::<Record1>(&[1, 0, 0, 0]);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
+
+fn load<T: native_model::Model>(data: &[u8]) -> native_model::Result<(T, u32)> {
+    native_model::decode_slice::<T>(data)
+}
+
+#[test]
+fn decode_generic_model() {
+    let owned = Owned {
+        name: "name".to_string(),
+    };
+    let owned_packed = native_model::encode(&owned).unwrap();
+    let (owned_decoded, _) = load::<Owned>(&owned_packed).unwrap();
+    assert_eq!(owned, owned_decoded);
+}
+
+#[test]
+fn decode_borrowing_model() {
+    let record = Record1 {
+        name: "name",
+        data: Cow::Borrowed(&[1, 2, 3]),
+    };
+    let record_packed = native_model::encode(&record).unwrap();
+    let result = native_model::decode::<Record1>(record_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DecodeBodyError(
+            native_model::DecodeBodyError::BorrowedModel
+        ))
+    ));
+}
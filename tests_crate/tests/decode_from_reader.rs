@@ -0,0 +1,232 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read};
+
+/// Reader failing once `capacity` bytes have been read.
+struct LimitedReader<'a> {
+    data: &'a [u8],
+    capacity: usize,
+}
+
+impl Read for LimitedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.capacity == 0 && !buf.is_empty() {
+            return Err(io::ErrorKind::ConnectionReset.into());
+        }
+        let len = buf.len().min(self.capacity);
+        let len = self.data.read(&mut buf[..len])?;
+        self.capacity -= len;
+        Ok(len)
+    }
+}
+
+macro_rules! decode_from_reader {
+    ($name:ident, $feature:literal, $with:path) => {
+        #[cfg(feature = $feature)]
+        mod $name {
+            use super::*;
+            use native_model::native_model;
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 1, version = 1, with = $with)]
+            struct Foo1 {
+                name: String,
+            }
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 1, version = 2, with = $with, from = Foo1)]
+            struct Foo2 {
+                name: String,
+                data: Vec<u8>,
+            }
+
+            impl From<Foo1> for Foo2 {
+                fn from(foo1: Foo1) -> Self {
+                    Foo2 {
+                        name: foo1.name,
+                        data: vec![],
+                    }
+                }
+            }
+
+            impl From<Foo2> for Foo1 {
+                fn from(foo2: Foo2) -> Self {
+                    Foo1 { name: foo2.name }
+                }
+            }
+
+            fn foo2() -> Foo2 {
+                Foo2 {
+                    name: "name".to_string(),
+                    data: vec![1; 64],
+                }
+            }
+
+            #[test]
+            fn decode_from_reader() {
+                let foo2_packed = native_model::encode(&foo2()).unwrap();
+                let (foo2_decoded, version) =
+                    native_model::decode_from_reader::<Foo2>(&foo2_packed[..]).unwrap();
+                assert_eq!(foo2(), foo2_decoded);
+                assert_eq!(version, 2);
+            }
+
+            #[test]
+            fn decode_from_reader_upgrade() {
+                let foo1_packed = native_model::encode(&Foo1 {
+                    name: "name".to_string(),
+                })
+                .unwrap();
+                let (foo2_decoded, version) =
+                    native_model::decode_from_reader::<Foo2>(&foo1_packed[..]).unwrap();
+                assert_eq!(
+                    Foo2 {
+                        name: "name".to_string(),
+                        data: vec![],
+                    },
+                    foo2_decoded
+                );
+                assert_eq!(version, 1);
+            }
+
+            #[test]
+            fn decode_from_reader_truncated() {
+                let foo2_packed = native_model::encode(&foo2()).unwrap();
+                for len in 0..8 {
+                    let result = native_model::decode_from_reader::<Foo2>(&foo2_packed[..len]);
+                    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+                }
+                for len in 8..foo2_packed.len() {
+                    let result = native_model::decode_from_reader::<Foo2>(&foo2_packed[..len]);
+                    assert!(matches!(result, Err(native_model::Error::Io(_))));
+                }
+            }
+
+            #[test]
+            fn decode_from_reader_error() {
+                let foo2_packed = native_model::encode(&foo2()).unwrap();
+                // Fails while reading the header, then while reading the body.
+                for capacity in [0, 4, 8, 12, foo2_packed.len() - 1] {
+                    let reader = LimitedReader {
+                        data: &foo2_packed,
+                        capacity,
+                    };
+                    let result = native_model::decode_from_reader::<Foo2>(reader);
+                    assert!(
+                        matches!(&result, Err(native_model::Error::Io(e)) if e.kind() == io::ErrorKind::ConnectionReset),
+                        "capacity {}: {:?}",
+                        capacity,
+                        result
+                    );
+                }
+            }
+
+            #[test]
+            fn decode_from_reader_with_limit() {
+                let foo2_packed = native_model::encode(&foo2()).unwrap();
+                let result = native_model::decode_from_reader_with_limit::<Foo2, 16>(&foo2_packed[..]);
+                assert!(matches!(result, Err(native_model::Error::Io(_))));
+
+                let (foo2_decoded, _) =
+                    native_model::decode_from_reader_with_limit::<Foo2, 1024>(&foo2_packed[..])
+                        .unwrap();
+                assert_eq!(foo2(), foo2_decoded);
+            }
+
+            #[test]
+            fn decode_from_reader_wrong_id() {
+                #[derive(Debug, Serialize, Deserialize, PartialEq)]
+                #[native_model(id = 2, version = 2, with = $with)]
+                struct Bar {
+                    name: String,
+                }
+
+                let foo2_packed = native_model::encode(&foo2()).unwrap();
+                let result = native_model::decode_from_reader::<Bar>(&foo2_packed[..]);
+                assert!(matches!(
+                    result,
+                    Err(native_model::Error::DecodeBodyError(
+                        native_model::DecodeBodyError::MismatchedModelId
+                    ))
+                ));
+            }
+        }
+    };
+}
+
+decode_from_reader!(
+    bincode_1_3,
+    "bincode_1_3",
+    native_model::bincode_1_3::Bincode
+);
+decode_from_reader!(bincode_2, "bincode_2", native_model::bincode_2::Bincode);
+decode_from_reader!(
+    postcard_1_0,
+    "postcard_1_0",
+    native_model::postcard_1_0::PostCard
+);
+decode_from_reader!(
+    rmp_serde_1_3,
+    "rmp_serde_1_3",
+    native_model::rmp_serde_1_3::RmpSerde
+);
+
+#[cfg(feature = "bincode_1_3")]
+#[test]
+fn decode_from_reader_only_reads_the_model() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model::native_model(id = 1, version = 1)]
+    struct Foo {
+        x: u32,
+        name: String,
+    }
+
+    // Models written back to back, like in a pipe.
+    let mut stream = vec![];
+    for x in 0..3 {
+        let foo = Foo {
+            x,
+            name: x.to_string(),
+        };
+        native_model::encode_to_writer(&foo, &mut stream).unwrap();
+    }
+
+    let mut reader = &stream[..];
+    for x in 0..3 {
+        let (foo, _) = native_model::decode_from_reader::<Foo>(&mut reader).unwrap();
+        assert_eq!(
+            Foo {
+                x,
+                name: x.to_string()
+            },
+            foo
+        );
+    }
+    assert!(reader.is_empty());
+}
+
+#[cfg(feature = "bincode_2")]
+#[test]
+fn decode_from_reader_with_limit_above_the_input() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model::native_model(id = 1, version = 1, with = native_model::bincode_2::Bincode)]
+    struct Numbers {
+        values: Vec<u128>,
+    }
+
+    // Each value takes a byte but claims 16, valid models may need a limit
+    // larger than their encoded size.
+    let numbers = Numbers {
+        values: vec![1; 1000],
+    };
+    let numbers_packed = native_model::encode(&numbers).unwrap();
+    assert!(numbers_packed.len() < 4096);
+
+    let result = native_model::decode_from_reader_with_limit::<Numbers, 4096>(&numbers_packed[..]);
+    assert!(matches!(result, Err(native_model::Error::Io(_))));
+
+    let (numbers_decoded, _) =
+        native_model::decode_from_reader_with_limit::<Numbers, 16384>(&numbers_packed[..]).unwrap();
+    assert_eq!(numbers, numbers_decoded);
+}
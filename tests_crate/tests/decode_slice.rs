@@ -0,0 +1,107 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::bincode_1_3::Bincode;
+use native_model::{native_model, Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// Codec refusing to decode owned bytes, proves that the body is never copied.
+pub struct SliceOnly;
+
+impl<T: Serialize> Encode<T> for SliceOnly {
+    type Error = anyhow::Error;
+    fn encode(obj: &T) -> Result<Vec<u8>, Self::Error> {
+        Ok(<Bincode as Encode<T>>::encode(obj)?)
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> Decode<T> for SliceOnly {
+    type Error = anyhow::Error;
+    fn decode(_data: Vec<u8>) -> Result<T, Self::Error> {
+        Err(anyhow::anyhow!("the body must not be copied"))
+    }
+
+    fn decode_slice(data: &[u8]) -> Result<T, Self::Error> {
+        Ok(<Bincode as Decode<T>>::decode_slice(data)?)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1, with = SliceOnly)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, with = SliceOnly, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    data: Vec<u8>,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            data: vec![],
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[test]
+fn decode_slice() {
+    let foo2 = Foo2 {
+        x: 1,
+        data: vec![1; 1024],
+    };
+    let foo2_packed = native_model::encode(&foo2).unwrap();
+    let (foo2_decoded, version) = native_model::decode_slice::<Foo2>(&foo2_packed).unwrap();
+    assert_eq!(foo2, foo2_decoded);
+    assert_eq!(version, 2);
+}
+
+#[test]
+fn decode_slice_upgrade() {
+    let foo1 = Foo1 { x: 1 };
+    let foo1_packed = native_model::encode(&foo1).unwrap();
+    let (foo2_decoded, version) = native_model::decode_slice::<Foo2>(&foo1_packed).unwrap();
+    assert_eq!(Foo2 { x: 1, data: vec![] }, foo2_decoded);
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn decode_slice_from_shared_buffer() {
+    // Models stored back to back in a single buffer, like in a memory mapped file.
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let foo2_packed = native_model::encode(&Foo2 {
+        x: 2,
+        data: vec![2; 16],
+    })
+    .unwrap();
+    let mut buffer = foo1_packed.clone();
+    buffer.extend_from_slice(&foo2_packed);
+
+    let (foo1_decoded, _) =
+        native_model::decode_slice::<Foo1>(&buffer[..foo1_packed.len()]).unwrap();
+    assert_eq!(Foo1 { x: 1 }, foo1_decoded);
+    let (foo2_decoded, _) =
+        native_model::decode_slice::<Foo2>(&buffer[foo1_packed.len()..]).unwrap();
+    assert_eq!(
+        Foo2 {
+            x: 2,
+            data: vec![2; 16]
+        },
+        foo2_decoded
+    );
+}
+
+#[test]
+fn decode_slice_invalid_header() {
+    let result = native_model::decode_slice::<Foo1>(&[1, 0, 0, 0]);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo {
+    x: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1, with = native_model::bincode_1_3::Bincode)]
+struct FooBincode {
+    x: u32,
+}
+
+#[test]
+fn default_codec_is_bincode_1_3() {
+    let foo = native_model::encode(&Foo { x: 1 }).unwrap();
+    let foo_bincode = native_model::encode(&FooBincode { x: 1 }).unwrap();
+    assert_eq!(foo, foo_bincode);
+
+    let body =
+        <native_model::bincode_1_3::Bincode as native_model::Encode<Foo>>::encode(&Foo { x: 1 })
+            .unwrap();
+    assert_eq!(&foo[8..], &body[..]);
+    assert_eq!(Foo::INFO.codec, "native_model::bincode_1_3::Bincode");
+}
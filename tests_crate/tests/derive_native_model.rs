@@ -0,0 +1,106 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{Model, NativeModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, NativeModel)]
+#[native_model(id = 1, version = 1)]
+struct UserV1 {
+    name: String,
+    #[native_model(removed_in = 2, default = "unknown".to_string())]
+    legacy: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, NativeModel)]
+#[native_model(id = 1, version = 2, from = UserV1)]
+struct UserV2 {
+    #[native_model(renamed_from = "name")]
+    display_name: String,
+    #[native_model(added_in = 2, default = 18)]
+    age: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, NativeModel)]
+#[native_model(id = 1, version = 3, from = UserV2)]
+struct UserV3 {
+    display_name: String,
+    #[native_model(added_in = 2)]
+    age: u32,
+    #[native_model(added_in = 3)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, NativeModel)]
+#[native_model(id = 2, version = 1, with = native_model::bincode_1_3::Bincode)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[test]
+fn derive_model() {
+    assert_eq!(UserV3::ID, 1);
+    assert_eq!(UserV3::VERSION, 3);
+
+    let encoded = native_model::encode(&Status::Active).unwrap();
+    let (decoded, _) = native_model::decode::<Status>(encoded).unwrap();
+    assert_eq!(decoded, Status::Active);
+    assert_ne!(decoded, Status::Inactive);
+}
+
+#[test]
+fn derive_upgrade() {
+    let user = UserV1 {
+        name: "Alice".to_string(),
+        legacy: "x".to_string(),
+    };
+    let encoded = native_model::encode(&user).unwrap();
+    let (decoded, version) = native_model::decode::<UserV3>(encoded).unwrap();
+    assert_eq!(
+        decoded,
+        UserV3 {
+            display_name: "Alice".to_string(),
+            age: 18,
+            tags: vec![],
+        }
+    );
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn derive_downgrade() {
+    let user = UserV3 {
+        display_name: "Alice".to_string(),
+        age: 30,
+        tags: vec!["admin".to_string()],
+    };
+    let encoded = native_model::encode_downgrade(user, 1).unwrap();
+    let (decoded, version) = native_model::decode::<UserV1>(encoded).unwrap();
+    assert_eq!(
+        decoded,
+        UserV1 {
+            name: "Alice".to_string(),
+            legacy: "unknown".to_string(),
+        }
+    );
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn derive_conversions() {
+    let user = UserV2::from(UserV1 {
+        name: "Bob".to_string(),
+        legacy: "x".to_string(),
+    });
+    assert_eq!(
+        user,
+        UserV2 {
+            display_name: "Bob".to_string(),
+            age: 18,
+        }
+    );
+    let user = UserV3::from(user);
+    assert_eq!(user.tags, Vec::<String>::new());
+    let user = UserV2::from(user);
+    assert_eq!(user.age, 18);
+}
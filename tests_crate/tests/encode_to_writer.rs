@@ -0,0 +1,152 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize, Serializer};
+use std::io::{self, Write};
+
+/// Writer failing once `capacity` bytes have been written.
+struct LimitedWriter {
+    data: Vec<u8>,
+    capacity: usize,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.capacity - self.data.len());
+        if len == 0 && !buf.is_empty() {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        self.data.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Value refusing to be serialized.
+#[derive(Debug, Deserialize, PartialEq)]
+struct Unserializable;
+
+impl Serialize for Unserializable {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("unserializable"))
+    }
+}
+
+macro_rules! encode_to_writer {
+    ($name:ident, $feature:literal, $with:path) => {
+        #[cfg(feature = $feature)]
+        mod $name {
+            use super::*;
+            use native_model::native_model;
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 1, version = 1, with = $with)]
+            struct Foo {
+                name: String,
+                data: Vec<u8>,
+            }
+
+            #[derive(Debug, Serialize, Deserialize, PartialEq)]
+            #[native_model(id = 2, version = 1, with = $with)]
+            struct Bar {
+                name: String,
+                value: Unserializable,
+            }
+
+            fn foo() -> Foo {
+                Foo {
+                    name: "name".to_string(),
+                    data: vec![1; 64],
+                }
+            }
+
+            #[test]
+            fn encode_into() {
+                let foo = foo();
+                let foo_packed = native_model::encode(&foo).unwrap();
+
+                let mut buffer = vec![0xff; 3];
+                native_model::encode_into(&foo, &mut buffer).unwrap();
+                assert_eq!(&buffer[..3], &[0xff; 3]);
+                assert_eq!(&buffer[3..], &foo_packed[..]);
+
+                let (foo_decoded, _) = native_model::decode_slice::<Foo>(&buffer[3..]).unwrap();
+                assert_eq!(foo, foo_decoded);
+            }
+
+            #[test]
+            fn encode_into_error_keeps_buffer() {
+                let bar = Bar {
+                    name: "name".to_string(),
+                    value: Unserializable,
+                };
+                let mut buffer = vec![0xff; 3];
+                let result = native_model::encode_into(&bar, &mut buffer);
+                assert!(matches!(result, Err(native_model::Error::EncodeBodyError(_))));
+                assert_eq!(buffer, vec![0xff; 3]);
+            }
+
+            #[test]
+            fn encode_to_writer() {
+                let foo = foo();
+                let foo_packed = native_model::encode(&foo).unwrap();
+
+                let mut writer = vec![];
+                native_model::encode_to_writer(&foo, &mut writer).unwrap();
+                assert_eq!(writer, foo_packed);
+            }
+
+            #[test]
+            fn encode_to_writer_error() {
+                let foo = foo();
+                let foo_packed = native_model::encode(&foo).unwrap();
+
+                // Fails while writing the header, then while writing the body.
+                for capacity in [0, 4, 8, 12, foo_packed.len() - 1] {
+                    let mut writer = LimitedWriter {
+                        data: vec![],
+                        capacity,
+                    };
+                    let result = native_model::encode_to_writer(&foo, &mut writer);
+                    assert!(
+                        matches!(&result, Err(native_model::Error::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe),
+                        "capacity {}: {:?}",
+                        capacity,
+                        result
+                    );
+                }
+
+                let bar = Bar {
+                    name: "name".to_string(),
+                    value: Unserializable,
+                };
+                let result = native_model::encode_to_writer(&bar, vec![]);
+                assert!(matches!(result, Err(native_model::Error::Io(e)) if e.kind() == io::ErrorKind::Other));
+            }
+        }
+    };
+}
+
+encode_to_writer!(
+    bincode_1_3,
+    "bincode_1_3",
+    native_model::bincode_1_3::Bincode
+);
+encode_to_writer!(bincode_2, "bincode_2", native_model::bincode_2::Bincode);
+encode_to_writer!(
+    postcard_1_0,
+    "postcard_1_0",
+    native_model::postcard_1_0::PostCard
+);
+encode_to_writer!(
+    rmp_serde_1_3,
+    "rmp_serde_1_3",
+    native_model::rmp_serde_1_3::RmpSerde
+);
+encode_to_writer!(
+    rmp_serde_1_3_named,
+    "rmp_serde_1_3",
+    native_model::rmp_serde_1_3::RmpSerdeNamed
+);
@@ -0,0 +1,150 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::frame::{FrameReader, FrameWriter};
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Bar {
+    data: Vec<u8>,
+}
+
+/// Stream holding `Foo1`, `Bar` and `Foo2` frames.
+fn stream() -> Vec<u8> {
+    let mut writer = FrameWriter::new(vec![]);
+    writer.write(&Foo1 { x: 1 }).unwrap();
+    writer.write(&Bar { data: vec![1; 32] }).unwrap();
+    writer
+        .write(&Foo2 {
+            x: 2,
+            name: "foo".to_string(),
+        })
+        .unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn frame_round_trip() {
+    let stream = stream();
+    let frames = FrameReader::new(&stream[..])
+        .collect::<native_model::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(frames.len(), 3);
+
+    assert_eq!((frames[0].id(), frames[0].version()), (1, 1));
+    assert_eq!(
+        frames[0].decode::<Foo2>().unwrap(),
+        (
+            Foo2 {
+                x: 1,
+                name: String::new()
+            },
+            1
+        )
+    );
+
+    assert_eq!((frames[1].id(), frames[1].version()), (2, 1));
+    assert_eq!(
+        frames[1].decode::<Bar>().unwrap(),
+        (Bar { data: vec![1; 32] }, 1)
+    );
+    assert_eq!(
+        frames[1].as_bytes(),
+        native_model::encode(&Bar { data: vec![1; 32] }).unwrap()
+    );
+
+    assert_eq!((frames[2].id(), frames[2].version()), (1, 2));
+    let (foo2, _) = frames[2].decode::<Foo2>().unwrap();
+    assert_eq!(foo2.name, "foo");
+}
+
+#[test]
+fn frame_skip_unknown_id() {
+    let stream = stream();
+    let mut reader = FrameReader::new(&stream[..]);
+    let mut foos = vec![];
+    while let Some(frame) = reader
+        .read_frame_if(|id, _| id == Foo2::native_model_id())
+        .unwrap()
+    {
+        foos.push(frame.decode::<Foo2>().unwrap().0);
+    }
+    assert_eq!(foos.len(), 2);
+    assert_eq!(foos[0].x, 1);
+    assert_eq!(foos[1].x, 2);
+    assert!(reader.into_inner().is_empty());
+}
+
+#[test]
+fn frame_truncated() {
+    let stream = stream();
+    let foo1_len = 4 + native_model::encode(&Foo1 { x: 1 }).unwrap().len();
+    let bar_len = 4 + native_model::encode(&Bar { data: vec![1; 32] })
+        .unwrap()
+        .len();
+    for len in 1..stream.len() {
+        let result = FrameReader::new(&stream[..len]).collect::<native_model::Result<Vec<_>>>();
+        if len == foo1_len {
+            assert_eq!(result.unwrap().len(), 1);
+        } else if len == foo1_len + bar_len {
+            assert_eq!(result.unwrap().len(), 2);
+        } else {
+            assert!(
+                matches!(&result, Err(native_model::Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof),
+                "len {}: {:?}",
+                len,
+                result
+            );
+        }
+    }
+    assert!(FrameReader::new(&[][..]).next().is_none());
+}
+
+#[test]
+fn frame_too_short_for_header() {
+    let stream = [4, 0, 0, 0, 1, 0, 0, 0];
+    let result = FrameReader::new(&stream[..]).read_frame();
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
+
+#[test]
+fn frame_wrong_id() {
+    let stream = stream();
+    let frame = FrameReader::new(&stream[..]).nth(1).unwrap().unwrap();
+    let result = frame.decode::<Foo1>();
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DecodeBodyError(
+            native_model::DecodeBodyError::MismatchedModelId
+        ))
+    ));
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, Model};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+#[native_model(id = 2, version = 1)]
+struct ResultsV1<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    items: Vec<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(bound = "T: Serialize + DeserializeOwned")]
+#[native_model(id = 2, version = 2, from = ResultsV1<T>)]
+struct ResultsV2<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    items: Vec<T>,
+    total: u32,
+}
+
+impl<T: Serialize + DeserializeOwned> From<ResultsV1<T>> for ResultsV2<T> {
+    fn from(results: ResultsV1<T>) -> Self {
+        ResultsV2 {
+            total: results.items.len() as u32,
+            items: results.items,
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> From<ResultsV2<T>> for ResultsV1<T> {
+    fn from(results: ResultsV2<T>) -> Self {
+        ResultsV1 {
+            items: results.items,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 1)]
+struct Labeled<'a, T> {
+    label: &'a str,
+    value: T,
+}
+
+#[test]
+fn encode_decode_generic_model() {
+    let page = Page {
+        items: vec![1u32, 2, 3],
+        next: Some(2),
+    };
+    let encoded = native_model::encode(&page).unwrap();
+    let (decoded, version) = native_model::decode::<Page<u32>>(encoded).unwrap();
+    assert_eq!(decoded, page);
+    assert_eq!(version, 1);
+
+    let page = Page {
+        items: vec!["a".to_string()],
+        next: None,
+    };
+    let encoded = native_model::encode(&page).unwrap();
+    let (decoded, _) = native_model::decode::<Page<String>>(encoded).unwrap();
+    assert_eq!(decoded, page);
+}
+
+#[test]
+fn generic_model_consts() {
+    assert_eq!(Page::<u32>::ID, 1);
+    assert_eq!(Page::<String>::VERSION, 1);
+    assert_eq!(ResultsV2::<u32>::VERSION, 2);
+    assert_eq!(Labeled::<'static, u32>::INFO.type_name, "Labeled");
+}
+
+#[test]
+fn upgrade_generic_model() {
+    let results = ResultsV1 {
+        items: vec![1u32, 2],
+    };
+    let encoded = native_model::encode(&results).unwrap();
+    let (decoded, version) = native_model::decode::<ResultsV2<u32>>(encoded).unwrap();
+    assert_eq!(
+        decoded,
+        ResultsV2 {
+            items: vec![1, 2],
+            total: 2
+        }
+    );
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn downgrade_generic_model() {
+    let results = ResultsV2 {
+        items: vec![1u32, 2],
+        total: 2,
+    };
+    let encoded = native_model::encode_downgrade(results, 1).unwrap();
+    let (decoded, _) = native_model::decode::<ResultsV1<u32>>(encoded).unwrap();
+    assert_eq!(decoded, ResultsV1 { items: vec![1, 2] });
+}
+
+#[test]
+fn decode_generic_model_from_reader() {
+    let page = Page {
+        items: vec![1u64],
+        next: None,
+    };
+    let mut buffer = Vec::new();
+    native_model::encode_to_writer(&page, &mut buffer).unwrap();
+    let (decoded, _) = native_model::decode_from_reader::<Page<u64>>(&buffer[..]).unwrap();
+    assert_eq!(decoded, page);
+}
+
+#[test]
+fn decode_borrowed_generic_model() {
+    let label = "answer".to_string();
+    let labeled = Labeled {
+        label: &label,
+        value: 42u32,
+    };
+    let encoded = native_model::encode(&labeled).unwrap();
+    let (decoded, _) = native_model::decode_borrowed::<Labeled<u32>>(&encoded).unwrap();
+    assert_eq!(decoded, labeled);
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 7, version = 1)]
+struct Foo1 {
+    x: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 7, version = 2, from = Foo1)]
+struct Foo2 {
+    x: u32,
+    y: u32,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(old: Foo1) -> Self {
+        Foo2 { x: old.x, y: 0 }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(old: Foo2) -> Self {
+        Foo1 { x: old.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 7, version = 4, from = Foo2)]
+struct Foo3 {
+    x: u64,
+    y: u64,
+}
+
+impl From<Foo2> for Foo3 {
+    fn from(old: Foo2) -> Self {
+        Foo3 {
+            x: old.x as u64,
+            y: old.y as u64,
+        }
+    }
+}
+
+impl From<Foo3> for Foo2 {
+    fn from(old: Foo3) -> Self {
+        Foo2 {
+            x: old.x as u32,
+            y: old.y as u32,
+        }
+    }
+}
+
+native_model::history! {
+    #[derive(Debug, PartialEq)]
+    id = 7;
+    Foo1, Foo2 => Foo3
+}
+
+mod dots {
+    use native_model::native_model;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model(id = 8, version = 1)]
+    pub struct DotV1(pub u32);
+
+    native_model::history! {
+        #[derive(Debug)]
+        pub type Point;
+        id = 8;
+        DotV1
+    }
+}
+
+mod shapes {
+    pub mod v1 {
+        use native_model::native_model;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        #[native_model(id = 10, version = 1)]
+        pub struct Shape {
+            pub sides: u32,
+        }
+    }
+
+    pub mod v2 {
+        use native_model::native_model;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        #[native_model(id = 10, version = 2, from = super::v1::Shape)]
+        pub struct Shape {
+            pub sides: u64,
+        }
+
+        impl From<super::v1::Shape> for Shape {
+            fn from(shape: super::v1::Shape) -> Self {
+                Shape {
+                    sides: shape.sides as u64,
+                }
+            }
+        }
+
+        impl From<Shape> for super::v1::Shape {
+            fn from(shape: Shape) -> Self {
+                super::v1::Shape {
+                    sides: shape.sides as u32,
+                }
+            }
+        }
+    }
+
+    // Same-named types are told apart by naming their variants.
+    native_model::history! {
+        #[derive(Debug)]
+        pub type Shape;
+        id = 10;
+        V1 = v1::Shape => V2 = v2::Shape
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 9, version = 1)]
+struct Bar {
+    x: u32,
+}
+
+#[test]
+fn latest_alias() {
+    let foo: FooLatest = Foo3 { x: 1, y: 2 };
+    assert_eq!(FooLatest::VERSION, 4);
+    assert_eq!(foo, Foo3 { x: 1, y: 2 });
+    assert_eq!(dots::PointLatest::ID, 8);
+}
+
+#[test]
+fn decode_any_without_upgrade() {
+    let encoded = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    match FooVersions::decode_any(&encoded).unwrap() {
+        FooVersions::Foo1(foo) => assert_eq!(foo, Foo1 { x: 1 }),
+        _ => panic!("expected Foo1"),
+    }
+
+    let encoded = native_model::encode(&Foo2 { x: 1, y: 2 }).unwrap();
+    let foo = FooVersions::decode_any(&encoded).unwrap();
+    assert_eq!(foo.version(), 2);
+    assert!(matches!(foo, FooVersions::Foo2(Foo2 { x: 1, y: 2 })));
+
+    let encoded = native_model::encode(&Foo3 { x: 1, y: 2 }).unwrap();
+    let foo = FooVersions::decode_any(&encoded).unwrap();
+    assert_eq!(foo.version(), 4);
+    assert!(matches!(foo, FooVersions::Foo3(Foo3 { x: 1, y: 2 })));
+
+    let encoded = native_model::encode(&dots::DotV1(3)).unwrap();
+    let dot = dots::PointVersions::decode_any(&encoded).unwrap();
+    assert!(matches!(dot, dots::PointVersions::DotV1(dots::DotV1(3))));
+}
+
+#[test]
+fn decode_any_named_variants() {
+    let encoded = native_model::encode(&shapes::v1::Shape { sides: 3 }).unwrap();
+    let shape = shapes::ShapeVersions::decode_any(&encoded).unwrap();
+    assert_eq!(shape.version(), 1);
+    assert!(matches!(
+        shape,
+        shapes::ShapeVersions::V1(shapes::v1::Shape { sides: 3 })
+    ));
+
+    let encoded = native_model::encode(&shapes::v2::Shape { sides: 4 }).unwrap();
+    let shape = shapes::ShapeVersions::decode_any(&encoded).unwrap();
+    assert!(matches!(
+        shape,
+        shapes::ShapeVersions::V2(shapes::v2::Shape { sides: 4 })
+    ));
+    assert_eq!(shapes::ShapeLatest::VERSION, 2);
+}
+
+#[test]
+fn forwarded_attributes() {
+    let encoded = native_model::encode(&Foo2 { x: 1, y: 2 }).unwrap();
+    let foo = FooVersions::decode_any(&encoded).unwrap();
+    assert_eq!(foo, FooVersions::Foo2(Foo2 { x: 1, y: 2 }));
+    assert_ne!(foo, FooVersions::Foo1(Foo1 { x: 1 }));
+    assert_eq!(format!("{:?}", foo), "Foo2(Foo2 { x: 1, y: 2 })");
+
+    let encoded = native_model::encode(&dots::DotV1(3)).unwrap();
+    let dot = dots::PointVersions::decode_any(&encoded).unwrap();
+    assert_eq!(format!("{:?}", dot), "DotV1(DotV1(3))");
+}
+
+#[test]
+fn decode_any_errors() {
+    let encoded = native_model::encode(&Bar { x: 1 }).unwrap();
+    let result = FooVersions::decode_any(&encoded);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::WrongTypeId {
+            expected: 7,
+            actual: 9
+        })
+    ));
+
+    // Version 3 isn't in the history.
+    let mut encoded = native_model::encode(&Foo3 { x: 1, y: 2 }).unwrap();
+    encoded[4..8].copy_from_slice(&3u32.to_le_bytes());
+    let result = FooVersions::decode_any(&encoded);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UnknownModelVersion { id: 7, version: 3 })
+    ));
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, NativeModelEnum};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Bar {
+    data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, NativeModelEnum)]
+enum Message {
+    Foo(Foo2),
+    Bar(Bar),
+}
+
+#[test]
+fn model_enum_decode() {
+    let bar_packed = native_model::encode(&Bar { data: vec![1] }).unwrap();
+    let (message, version) = Message::native_model_decode(&bar_packed).unwrap();
+    assert_eq!(message, Message::Bar(Bar { data: vec![1] }));
+    assert_eq!(version, 1);
+
+    let foo2 = Foo2 {
+        x: 2,
+        name: "foo".to_string(),
+    };
+    let foo2_packed = native_model::encode(&foo2).unwrap();
+    let (message, version) = Message::native_model_decode(foo2_packed).unwrap();
+    assert_eq!(message, Message::Foo(foo2));
+    assert_eq!(version, 2);
+}
+
+#[test]
+fn model_enum_decode_upgrade() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (message, version) = Message::native_model_decode(&foo1_packed).unwrap();
+    assert_eq!(
+        message,
+        Message::Foo(Foo2 {
+            x: 1,
+            name: String::new()
+        })
+    );
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn model_enum_encode() {
+    let foo2 = Foo2 {
+        x: 2,
+        name: "foo".to_string(),
+    };
+    let foo2_packed = native_model::encode(&foo2).unwrap();
+    assert_eq!(
+        Message::Foo(foo2).native_model_encode().unwrap(),
+        foo2_packed
+    );
+
+    let message = Message::Bar(Bar { data: vec![1, 2] });
+    let message_packed = message.native_model_encode().unwrap();
+    assert_eq!(
+        native_model::decode::<Bar>(message_packed).unwrap().0,
+        Bar { data: vec![1, 2] }
+    );
+}
+
+#[test]
+fn model_enum_unknown_id() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model(id = 3, version = 1)]
+    struct Baz;
+
+    let baz_packed = native_model::encode(&Baz).unwrap();
+    let result = Message::native_model_decode(&baz_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UnknownModelId { id: 3 })
+    ));
+    let result = Message::native_model_decode(&baz_packed[..4]);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, Model, ModelInfo};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3)]
+struct Foo {
+    x: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1, with = native_model::bincode_1_3::Bincode)]
+struct Bar {
+    x: u32,
+}
+
+mod ids {
+    pub const BAZ: u32 = 3;
+}
+
+const BAZ_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = ids::BAZ, version = BAZ_VERSION)]
+struct Baz {
+    x: u32,
+}
+
+const _: () = assert!(Foo::ID != Bar::ID);
+
+static MODELS: [ModelInfo; 2] = [Foo::INFO, Bar::INFO];
+
+fn name(data: &[u8]) -> &'static str {
+    match native_model::peek(data).unwrap().id {
+        Foo::ID => "foo",
+        Bar::ID => "bar",
+        _ => "unknown",
+    }
+}
+
+#[test]
+fn consts() {
+    assert_eq!(Foo::ID, 1);
+    assert_eq!(Foo::VERSION, 3);
+    assert_eq!(Foo::ID, Foo::native_model_id());
+    assert_eq!(Foo::VERSION, Foo::native_model_version());
+
+    let versions = [0u8; Foo::VERSION as usize];
+    assert_eq!(versions.len(), 3);
+
+    let foo_packed = native_model::encode(&Foo { x: 1 }).unwrap();
+    let bar_packed = native_model::encode(&Bar { x: 1 }).unwrap();
+    assert_eq!(name(&foo_packed), "foo");
+    assert_eq!(name(&bar_packed), "bar");
+}
+
+#[test]
+fn info() {
+    assert_eq!(Foo::INFO.id, 1);
+    assert_eq!(Foo::INFO.version, 3);
+    assert_eq!(Foo::INFO.type_name, "Foo");
+    assert_eq!(Foo::INFO.codec, "native_model::bincode_1_3::Bincode");
+    assert_eq!(Bar::INFO.type_name, "Bar");
+    assert_eq!(Bar::INFO.codec, "native_model::bincode_1_3::Bincode");
+
+    let ids = MODELS.iter().map(|info| info.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[test]
+fn const_id_and_version() {
+    assert_eq!(Baz::ID, 3);
+    assert_eq!(Baz::VERSION, 2);
+    assert_eq!(Baz::native_model_id_str(), "3");
+    assert_eq!(Baz::native_model_version_str(), "2");
+    assert_eq!(Foo::native_model_id_str(), "1");
+
+    let baz_packed = native_model::encode(&Baz { x: 1 }).unwrap();
+    let header = native_model::peek(&baz_packed).unwrap();
+    assert_eq!((header.id, header.version), (3, 2));
+    let (baz, _) = native_model::decode::<Baz>(baz_packed).unwrap();
+    assert_eq!(baz, Baz { x: 1 });
+}
@@ -0,0 +1,237 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::native_model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+// Only `From<Foo1> for Foo2`, no downgrade.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, upgrade_from = Foo1)]
+struct Foo2 {
+    x: i64,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 { x: foo1.x.into() }
+    }
+}
+
+// Only `TryFrom<Foo2> for Foo3`, no downgrade.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3, try_upgrade_from = (Foo2, anyhow::Error))]
+struct Foo3 {
+    x: u64,
+}
+
+impl TryFrom<Foo2> for Foo3 {
+    type Error = anyhow::Error;
+
+    fn try_from(foo2: Foo2) -> Result<Self, Self::Error> {
+        Ok(Foo3 {
+            x: foo2.x.try_into()?,
+        })
+    }
+}
+
+#[test]
+fn upgrade_only() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (foo2, version) = native_model::decode::<Foo2>(foo1_packed.clone()).unwrap();
+    assert_eq!(foo2, Foo2 { x: 1 });
+    assert_eq!(version, 1);
+
+    let (foo3, version) = native_model::decode::<Foo3>(foo1_packed.clone()).unwrap();
+    assert_eq!(foo3, Foo3 { x: 1 });
+    assert_eq!(version, 1);
+
+    let (foo3, _) = native_model::decode_from_reader::<Foo3>(&foo1_packed[..]).unwrap();
+    assert_eq!(foo3, Foo3 { x: 1 });
+
+    let (_, report) = native_model::decode_with_report::<Foo3>(&foo1_packed).unwrap();
+    let steps = report
+        .steps
+        .iter()
+        .map(|step| (step.from_version, step.to_version, step.fallible))
+        .collect::<Vec<_>>();
+    assert_eq!(steps, vec![(1, 2, false), (2, 3, true)]);
+
+    let foo1_packed = native_model::encode(&Foo1 { x: -1 }).unwrap();
+    let result = native_model::decode::<Foo3>(foo1_packed);
+    assert!(matches!(result, Err(native_model::Error::UpgradeError(_))));
+}
+
+#[test]
+fn try_upgrade_from_list() {
+    // Fallible shortcuts from `Foo1` and `Foo3`, with different errors.
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model(
+        id = 1,
+        version = 4,
+        try_upgrade_from = [(Foo1, anyhow::Error), (Foo3, std::num::TryFromIntError)]
+    )]
+    struct Foo4 {
+        x: u8,
+    }
+
+    impl TryFrom<Foo1> for Foo4 {
+        type Error = anyhow::Error;
+
+        fn try_from(foo1: Foo1) -> Result<Self, Self::Error> {
+            Ok(Foo4 {
+                x: foo1.x.try_into()?,
+            })
+        }
+    }
+
+    impl TryFrom<Foo3> for Foo4 {
+        type Error = std::num::TryFromIntError;
+
+        fn try_from(foo3: Foo3) -> Result<Self, Self::Error> {
+            Ok(Foo4 {
+                x: foo3.x.try_into()?,
+            })
+        }
+    }
+
+    let versions = |packed: &[u8]| {
+        let (_, report) = native_model::decode_with_report::<Foo4>(packed).unwrap();
+        report
+            .steps
+            .iter()
+            .map(|step| (step.from_version, step.to_version, step.fallible))
+            .collect::<Vec<_>>()
+    };
+
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (foo4, version) = native_model::decode::<Foo4>(foo1_packed.clone()).unwrap();
+    assert_eq!(foo4, Foo4 { x: 1 });
+    assert_eq!(version, 1);
+    assert_eq!(versions(&foo1_packed), vec![(1, 4, true)]);
+
+    let foo2_packed = native_model::encode(&Foo2 { x: 2 }).unwrap();
+    let (foo4, _) = native_model::decode::<Foo4>(foo2_packed.clone()).unwrap();
+    assert_eq!(foo4, Foo4 { x: 2 });
+    assert_eq!(versions(&foo2_packed), vec![(2, 3, true), (3, 4, true)]);
+
+    let foo3_packed = native_model::encode(&Foo3 { x: 300 }).unwrap();
+    let result = native_model::decode::<Foo4>(foo3_packed);
+    assert!(matches!(result, Err(native_model::Error::UpgradeError(_))));
+}
+
+#[test]
+fn upgrade_only_downgrade_not_supported() {
+    let result = native_model::encode_downgrade(Foo2 { x: 1 }, 1);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DowngradeNotSupported { from: 1, to: 2 })
+    ));
+
+    let result = native_model::encode_downgrade(Foo3 { x: 1 }, 2);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DowngradeNotSupported { from: 2, to: 3 })
+    ));
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Bar1 {
+    x: i32,
+}
+
+// Only `From<Bar2> for Bar1`, no upgrade.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 2, downgrade_to = Bar1)]
+struct Bar2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Bar2> for Bar1 {
+    fn from(bar2: Bar2) -> Self {
+        Bar1 { x: bar2.x }
+    }
+}
+
+// Only `TryFrom<Bar3> for Bar2`, no upgrade.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 3, try_downgrade_to = (Bar2, anyhow::Error))]
+struct Bar3 {
+    x: i64,
+    name: String,
+}
+
+impl TryFrom<Bar3> for Bar2 {
+    type Error = anyhow::Error;
+
+    fn try_from(bar3: Bar3) -> Result<Self, Self::Error> {
+        Ok(Bar2 {
+            x: bar3.x.try_into()?,
+            name: bar3.name,
+        })
+    }
+}
+
+#[test]
+fn downgrade_only() {
+    let bar3 = Bar3 {
+        x: 3,
+        name: "bar".to_string(),
+    };
+    let bar1_packed = native_model::encode_downgrade(bar3, 1).unwrap();
+    let (bar1, version) = native_model::decode::<Bar1>(bar1_packed).unwrap();
+    assert_eq!(bar1, Bar1 { x: 3 });
+    assert_eq!(version, 1);
+
+    let bar3 = Bar3 {
+        x: i64::MAX,
+        name: "bar".to_string(),
+    };
+    let result = native_model::encode_downgrade(bar3, 2);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DowngradeError(_))
+    ));
+}
+
+#[test]
+fn downgrade_only_upgrade_not_supported() {
+    let bar1_packed = native_model::encode(&Bar1 { x: 1 }).unwrap();
+    let result = native_model::decode::<Bar2>(bar1_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UpgradeNotSupported { from: 1, to: 2 })
+    ));
+}
+
+#[test]
+fn upgrade_from_and_downgrade_to() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model(id = 1, version = 2, upgrade_from = Foo1, downgrade_to = Foo1)]
+    struct Baz2 {
+        x: i32,
+    }
+
+    impl From<Foo1> for Baz2 {
+        fn from(foo1: Foo1) -> Self {
+            Baz2 { x: foo1.x }
+        }
+    }
+
+    impl From<Baz2> for Foo1 {
+        fn from(baz2: Baz2) -> Self {
+            Foo1 { x: baz2.x }
+        }
+    }
+
+    let foo1_packed = native_model::encode_downgrade(Baz2 { x: 1 }, 1).unwrap();
+    let (baz2, version) = native_model::decode::<Baz2>(foo1_packed).unwrap();
+    assert_eq!(baz2, Baz2 { x: 1 });
+    assert_eq!(version, 1);
+}
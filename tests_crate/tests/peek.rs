@@ -0,0 +1,85 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 3)]
+struct Bar {
+    data: Vec<u8>,
+}
+
+#[test]
+fn peek() {
+    let bar_packed = native_model::encode(&Bar { data: vec![1; 16] }).unwrap();
+    let header = native_model::peek(&bar_packed).unwrap();
+    assert_eq!(header.id, 2);
+    assert_eq!(header.version, 3);
+    assert_eq!(header.body_len, bar_packed.len() - 8);
+
+    let foo1_packed = native_model::encode_downgrade(
+        Foo2 {
+            x: 1,
+            name: "foo".to_string(),
+        },
+        1,
+    )
+    .unwrap();
+    let header = native_model::peek(&foo1_packed).unwrap();
+    assert_eq!((header.id, header.version), (1, 1));
+}
+
+#[test]
+fn peek_empty_body() {
+    let header = native_model::peek(&[1, 0, 0, 0, 2, 0, 0, 0]).unwrap();
+    assert_eq!((header.id, header.version, header.body_len), (1, 2, 0));
+}
+
+#[test]
+fn peek_invalid_header() {
+    for len in 0..8 {
+        let result = native_model::peek(&[0; 8][..len]);
+        assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+    }
+}
+
+#[test]
+fn native_model_matches() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let bar_packed = native_model::encode(&Bar { data: vec![] }).unwrap();
+
+    assert!(Foo1::native_model_matches(&foo1_packed));
+    assert!(Foo2::native_model_matches(&foo1_packed));
+    assert!(!Bar::native_model_matches(&foo1_packed));
+    assert!(Bar::native_model_matches(&bar_packed));
+    assert!(!Foo2::native_model_matches(&bar_packed));
+    assert!(!Foo2::native_model_matches(&foo1_packed[..4]));
+}
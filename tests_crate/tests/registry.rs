@@ -0,0 +1,160 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::native_model;
+use native_model::registry::ModelRegistry;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Bar {
+    data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Foo(Foo2),
+    Bar(Bar),
+}
+
+#[test]
+fn registry_any() {
+    let mut registry = ModelRegistry::new();
+    registry.register::<Foo2>().unwrap();
+    registry.register::<Bar>().unwrap();
+    assert!(registry.contains(1));
+    assert!(registry.contains(2));
+    assert!(!registry.contains(3));
+
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (foo2, version) = registry.decode(&foo1_packed).unwrap();
+    assert_eq!(
+        foo2.downcast_ref::<Foo2>(),
+        Some(&Foo2 {
+            x: 1,
+            name: String::new()
+        })
+    );
+    assert_eq!(version, 1);
+
+    let bar_packed = native_model::encode(&Bar { data: vec![1, 2] }).unwrap();
+    let (bar, version) = registry.decode(&bar_packed).unwrap();
+    assert_eq!(*bar.downcast::<Bar>().unwrap(), Bar { data: vec![1, 2] });
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn registry_callback() {
+    let mut registry = ModelRegistry::new();
+    registry
+        .register_with(|foo2: Foo2, _| Event::Foo(foo2))
+        .unwrap();
+    registry
+        .register_with(|bar: Bar, _| Event::Bar(bar))
+        .unwrap();
+
+    let events = [
+        native_model::encode(&Bar { data: vec![] }).unwrap(),
+        native_model::encode(&Foo1 { x: 1 }).unwrap(),
+        native_model::encode(&Foo2 {
+            x: 2,
+            name: "foo".to_string(),
+        })
+        .unwrap(),
+    ]
+    .iter()
+    .map(|bytes| registry.decode(bytes).unwrap())
+    .collect::<Vec<_>>();
+
+    assert_eq!(
+        events,
+        vec![
+            Event::Bar(Bar { data: vec![] }),
+            Event::Foo(Foo2 {
+                x: 1,
+                name: String::new()
+            }),
+            Event::Foo(Foo2 {
+                x: 2,
+                name: "foo".to_string()
+            }),
+        ]
+    );
+}
+
+#[test]
+fn registry_duplicate_id() {
+    let mut registry = ModelRegistry::new();
+    registry.register::<Foo2>().unwrap();
+    let result = registry.register::<Foo1>();
+    assert!(matches!(
+        result,
+        Err(native_model::Error::DuplicateModelId { id: 1 })
+    ));
+}
+
+#[test]
+fn registry_unknown_id() {
+    let mut registry = ModelRegistry::new();
+    registry.register::<Bar>().unwrap();
+
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let result = registry.decode(&foo1_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UnknownModelId { id: 1 })
+    ));
+    let result = registry.decode(&foo1_packed[..4]);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
+
+#[test]
+fn registry_shared_between_threads() {
+    let mut registry = ModelRegistry::new();
+    registry
+        .register_with(|bar: Bar, _| bar.data.len())
+        .unwrap();
+    let registry = std::sync::Arc::new(registry);
+
+    let handles = (0..4)
+        .map(|len| {
+            let registry = registry.clone();
+            std::thread::spawn(move || {
+                let bar_packed = native_model::encode(&Bar { data: vec![0; len] }).unwrap();
+                registry.decode(&bar_packed).unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+    let lens = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lens, vec![0, 1, 2, 3]);
+}
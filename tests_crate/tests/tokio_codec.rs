@@ -0,0 +1,191 @@
+#![cfg(feature = "tokio")]
+
+use futures_util::{SinkExt, StreamExt};
+use native_model::frame::{FrameReader, FrameWriter};
+use native_model::tokio_codec::{FrameCodec, ModelCodec};
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio_util::codec::{Decoder, FramedRead, FramedWrite};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Bar {
+    data: Vec<u8>,
+}
+
+#[tokio::test]
+async fn model_codec() {
+    // A small duplex buffer forces the frames to be split across reads.
+    let (client, server) = tokio::io::duplex(16);
+
+    let writer = tokio::spawn(async move {
+        let mut sink = FramedWrite::new(client, ModelCodec::<Foo2>::new());
+        for x in 0..10 {
+            let foo2 = Foo2 {
+                x,
+                name: "foo".repeat(x as usize),
+            };
+            sink.send(&foo2).await.unwrap();
+        }
+    });
+
+    let mut stream = FramedRead::new(server, ModelCodec::<Foo2>::new());
+    for x in 0..10 {
+        let (foo2, version) = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            Foo2 {
+                x,
+                name: "foo".repeat(x as usize),
+            },
+            foo2
+        );
+        assert_eq!(version, 2);
+    }
+    writer.await.unwrap();
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn model_codec_upgrade() {
+    let (client, server) = tokio::io::duplex(64);
+
+    let mut sink = FramedWrite::new(client, ModelCodec::<Foo1>::new());
+    sink.send(Foo1 { x: 1 }).await.unwrap();
+    drop(sink);
+
+    let mut stream = FramedRead::new(server, ModelCodec::<Foo2>::new());
+    let (foo2, version) = stream.next().await.unwrap().unwrap();
+    assert_eq!(
+        Foo2 {
+            x: 1,
+            name: String::new()
+        },
+        foo2
+    );
+    assert_eq!(version, 1);
+}
+
+#[tokio::test]
+async fn frame_codec_heterogeneous() {
+    let (client, server) = tokio::io::duplex(64);
+
+    let writer = tokio::spawn(async move {
+        let mut sink = FramedWrite::new(client, FrameCodec::new());
+        sink.send(&Foo1 { x: 1 }).await.unwrap();
+        sink.send(&Bar { data: vec![1; 100] }).await.unwrap();
+        sink.send(&Foo2 {
+            x: 2,
+            name: "foo".to_string(),
+        })
+        .await
+        .unwrap();
+    });
+
+    let mut foos = vec![];
+    let mut bars = vec![];
+    let mut stream = FramedRead::new(server, FrameCodec::new());
+    while let Some(frame) = stream.next().await {
+        let frame = frame.unwrap();
+        if frame.id() == Foo2::native_model_id() {
+            foos.push(frame.decode::<Foo2>().unwrap().0);
+        } else if frame.id() == Bar::native_model_id() {
+            bars.push(frame.decode::<Bar>().unwrap().0);
+        }
+    }
+    writer.await.unwrap();
+
+    assert_eq!(foos.iter().map(|foo| foo.x).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(bars, vec![Bar { data: vec![1; 100] }]);
+}
+
+#[tokio::test]
+async fn frame_codec_forward() {
+    let (client, server) = tokio::io::duplex(64);
+
+    // Frames read from a synchronous stream are forwarded as they are.
+    let mut writer = FrameWriter::new(vec![]);
+    writer.write(&Foo1 { x: 1 }).unwrap();
+    writer.write(&Bar { data: vec![2; 8] }).unwrap();
+    let stream = writer.into_inner();
+
+    let mut sink = FramedWrite::new(client, FrameCodec::new());
+    for frame in FrameReader::new(&stream[..]) {
+        sink.send(frame.unwrap()).await.unwrap();
+    }
+    drop(sink);
+
+    let frames = FramedRead::new(server, FrameCodec::new())
+        .map(|frame| frame.unwrap().into_bytes())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        frames,
+        vec![
+            native_model::encode(&Foo1 { x: 1 }).unwrap(),
+            native_model::encode(&Bar { data: vec![2; 8] }).unwrap(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn frame_codec_max_frame_len() {
+    let (client, server) = tokio::io::duplex(256);
+
+    let mut sink = FramedWrite::new(client, FrameCodec::new().max_frame_len(32));
+    let result = sink.send(&Bar { data: vec![1; 64] }).await;
+    assert!(matches!(
+        result,
+        Err(native_model::Error::FrameTooLarge { .. })
+    ));
+    // The failed frame isn't left in the buffer.
+    sink.send(&Bar { data: vec![1; 4] }).await.unwrap();
+    let mut client = sink.into_inner();
+    // Claims a frame far larger than the limit of the reader.
+    client.write_all(&u32::MAX.to_le_bytes()).await.unwrap();
+    drop(client);
+
+    let mut stream = FramedRead::new(server, FrameCodec::new().max_frame_len(32));
+    let frame = stream.next().await.unwrap().unwrap();
+    assert_eq!(frame.decode::<Bar>().unwrap().0, Bar { data: vec![1; 4] });
+    let result = stream.next().await.unwrap();
+    assert!(matches!(
+        result,
+        Err(native_model::Error::FrameTooLarge { len }) if len == u32::MAX as usize
+    ));
+}
+
+#[test]
+fn frame_codec_invalid_header() {
+    let mut src = [4, 0, 0, 0, 1, 0, 0, 0][..].into();
+    let result = FrameCodec::new().decode(&mut src);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+use native_model::bincode_1_3::Bincode;
+
+/// Codec failing to encode, proves that up to date bytes aren't encoded again.
+pub struct DecodeOnly;
+
+impl<T> Encode<T> for DecodeOnly {
+    type Error = anyhow::Error;
+    fn encode(_obj: &T) -> Result<Vec<u8>, Self::Error> {
+        Err(anyhow::anyhow!("encoding is not supported"))
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> Decode<T> for DecodeOnly {
+    type Error = anyhow::Error;
+    fn decode(data: Vec<u8>) -> Result<T, Self::Error> {
+        Ok(<Bincode as Decode<T>>::decode(data)?)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3, try_from = (Foo2, anyhow::Error))]
+struct Foo3 {
+    x: i64,
+    name: String,
+}
+
+impl TryFrom<Foo2> for Foo3 {
+    type Error = anyhow::Error;
+
+    fn try_from(foo2: Foo2) -> Result<Self, Self::Error> {
+        if foo2.x < 0 {
+            return Err(anyhow::anyhow!("negative x"));
+        }
+        Ok(Foo3 {
+            x: foo2.x as i64,
+            name: foo2.name,
+        })
+    }
+}
+
+impl TryFrom<Foo3> for Foo2 {
+    type Error = anyhow::Error;
+
+    fn try_from(foo3: Foo3) -> Result<Self, Self::Error> {
+        Ok(Foo2 {
+            x: foo3.x as i32,
+            name: foo3.name,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 3, with = DecodeOnly)]
+struct Bar {
+    x: i32,
+}
+
+#[test]
+fn upgrade_bytes() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let foo3_packed = native_model::upgrade_bytes::<Foo3>(&foo1_packed).unwrap();
+    assert_eq!(native_model::peek(&foo3_packed).unwrap().version, 3);
+    assert_eq!(
+        native_model::decode::<Foo3>(foo3_packed.clone()).unwrap(),
+        (
+            Foo3 {
+                x: 1,
+                name: String::new()
+            },
+            3
+        )
+    );
+    assert_eq!(
+        foo3_packed,
+        native_model::encode(&Foo3 {
+            x: 1,
+            name: String::new()
+        })
+        .unwrap()
+    );
+
+    let foo2_packed = native_model::encode(&Foo2 {
+        x: 2,
+        name: "foo".to_string(),
+    })
+    .unwrap();
+    let foo2_upgraded = native_model::upgrade_bytes::<Foo2>(&foo2_packed).unwrap();
+    assert_eq!(foo2_upgraded, foo2_packed);
+}
+
+#[test]
+fn upgrade_bytes_up_to_date() {
+    // Hand made bytes of `Bar`, which can't be encoded.
+    let mut bar_packed = vec![2, 0, 0, 0, 3, 0, 0, 0];
+    bar_packed.extend(bincode_1_3_bytes(7));
+    let bar_upgraded = native_model::upgrade_bytes::<Bar>(&bar_packed).unwrap();
+    assert_eq!(bar_upgraded, bar_packed);
+}
+
+#[test]
+fn upgrade_bytes_errors() {
+    let foo1_packed = native_model::encode(&Foo1 { x: -1 }).unwrap();
+    let result = native_model::upgrade_bytes::<Foo3>(&foo1_packed);
+    assert!(matches!(result, Err(native_model::Error::UpgradeError(_))));
+
+    let result = native_model::upgrade_bytes::<Bar>(&foo1_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::WrongTypeId {
+            expected: 2,
+            actual: 1
+        })
+    ));
+
+    let foo3_packed = native_model::encode(&Foo3 {
+        x: 3,
+        name: String::new(),
+    })
+    .unwrap();
+    let result = native_model::upgrade_bytes::<Foo2>(&foo3_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UpgradeNotSupported { from: 3, to: 2 })
+    ));
+
+    let result = native_model::upgrade_bytes::<Foo3>(&foo3_packed[..4]);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
+
+fn bincode_1_3_bytes(x: i32) -> Vec<u8> {
+    <Bincode as Encode<i32>>::encode(&x).unwrap()
+}
@@ -0,0 +1,318 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::native_model;
+use serde::{Deserialize, Serialize};
+
+// Each model records the versions it was upgraded from in `path`.
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3, from = Foo2)]
+struct Foo3 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 4, from = Foo3, upgrade_from = [Foo1])]
+struct Foo4 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 5, from = Foo4, upgrade_from = [Foo2, Foo3])]
+struct Foo5 {
+    path: Vec<u32>,
+}
+
+macro_rules! upgrade {
+    ($from:ident, $from_version:literal, $to:ident) => {
+        impl From<$from> for $to {
+            fn from(from: $from) -> Self {
+                let mut path = from.path;
+                path.push($from_version);
+                $to { path }
+            }
+        }
+    };
+}
+
+upgrade!(Foo1, 1, Foo2);
+upgrade!(Foo2, 2, Foo3);
+upgrade!(Foo3, 3, Foo4);
+upgrade!(Foo4, 4, Foo5);
+upgrade!(Foo1, 1, Foo4);
+upgrade!(Foo2, 2, Foo5);
+upgrade!(Foo3, 3, Foo5);
+
+// Downgrades, required by `from`.
+upgrade!(Foo2, 2, Foo1);
+upgrade!(Foo3, 3, Foo2);
+upgrade!(Foo4, 4, Foo3);
+upgrade!(Foo5, 5, Foo4);
+
+fn encode_foo1() -> Vec<u8> {
+    native_model::encode(&Foo1 { path: vec![] }).unwrap()
+}
+
+#[test]
+fn upgrade_from_shortcut() {
+    let (foo4, version) = native_model::decode::<Foo4>(encode_foo1()).unwrap();
+    assert_eq!(foo4, Foo4 { path: vec![1] });
+    assert_eq!(version, 1);
+
+    // The shortcut from `Foo1` doesn't apply to `Foo2`.
+    let foo2_packed = native_model::encode(&Foo2 { path: vec![] }).unwrap();
+    let (foo4, _) = native_model::decode::<Foo4>(foo2_packed).unwrap();
+    assert_eq!(foo4, Foo4 { path: vec![2, 3] });
+}
+
+#[test]
+fn upgrade_from_shortest_path() {
+    // Through `Foo4` and its shortcut, rather than through `Foo2`.
+    let (foo5, _) = native_model::decode::<Foo5>(encode_foo1()).unwrap();
+    assert_eq!(foo5, Foo5 { path: vec![1, 4] });
+
+    let foo2_packed = native_model::encode(&Foo2 { path: vec![] }).unwrap();
+    let (foo5, _) = native_model::decode::<Foo5>(foo2_packed).unwrap();
+    assert_eq!(foo5, Foo5 { path: vec![2] });
+
+    // `from` is preferred when it is as short.
+    let foo3_packed = native_model::encode(&Foo3 { path: vec![] }).unwrap();
+    let (foo5, _) = native_model::decode::<Foo5>(foo3_packed).unwrap();
+    assert_eq!(foo5, Foo5 { path: vec![3] });
+
+    let foo4_packed = native_model::encode(&Foo4 { path: vec![] }).unwrap();
+    let (foo5, _) = native_model::decode::<Foo5>(foo4_packed).unwrap();
+    assert_eq!(foo5, Foo5 { path: vec![4] });
+}
+
+#[test]
+fn upgrade_from_cost() {
+    use native_model::Model;
+
+    assert_eq!(Foo5::native_model_upgrade_cost(5), Some(0));
+    assert_eq!(Foo5::native_model_upgrade_cost(4), Some(1));
+    assert_eq!(Foo5::native_model_upgrade_cost(2), Some(1));
+    assert_eq!(Foo5::native_model_upgrade_cost(1), Some(2));
+    assert_eq!(Foo5::native_model_upgrade_cost(0), None);
+    assert_eq!(Foo5::native_model_upgrade_cost(6), None);
+    assert_eq!(Foo3::native_model_upgrade_cost(1), Some(2));
+}
+
+#[test]
+fn upgrade_from_report() {
+    let (_, report) = native_model::decode_with_report::<Foo5>(&encode_foo1()).unwrap();
+    let versions = report
+        .steps
+        .iter()
+        .map(|step| (step.from_version, step.to_version))
+        .collect::<Vec<_>>();
+    assert_eq!(versions, vec![(1, 4), (4, 5)]);
+    assert!(report.steps[0].from_type.ends_with("Foo1"));
+    assert!(report.steps[0].to_type.ends_with("Foo4"));
+}
+
+#[test]
+fn upgrade_from_reader() {
+    let foo1_packed = encode_foo1();
+    let (foo5, version) = native_model::decode_from_reader::<Foo5>(&foo1_packed[..]).unwrap();
+    assert_eq!(foo5, Foo5 { path: vec![1, 4] });
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn upgrade_from_only() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[native_model(id = 1, version = 9, upgrade_from = [Foo1, Foo3])]
+    struct Foo9 {
+        path: Vec<u32>,
+    }
+
+    upgrade!(Foo1, 1, Foo9);
+    upgrade!(Foo3, 3, Foo9);
+
+    let (foo9, _) = native_model::decode::<Foo9>(encode_foo1()).unwrap();
+    assert_eq!(foo9, Foo9 { path: vec![1] });
+
+    let foo2_packed = native_model::encode(&Foo2 { path: vec![] }).unwrap();
+    let (foo9, _) = native_model::decode::<Foo9>(foo2_packed).unwrap();
+    assert_eq!(foo9, Foo9 { path: vec![2, 3] });
+
+    let foo5_packed = native_model::encode(&Foo5 { path: vec![] }).unwrap();
+    let result = native_model::decode::<Foo9>(foo5_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UpgradeNotSupported { from: 5, to: 9 })
+    ));
+}
+
+// Each version has a shortcut from the version before the previous one, the
+// costs of the 40 versions must be computed once per version rather than once
+// per path.
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 1)]
+struct Bar1 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 2, version = 2, from = Bar1)]
+struct Bar2 {
+    path: Vec<u32>,
+}
+
+upgrade!(Bar1, 1, Bar2);
+upgrade!(Bar2, 2, Bar1);
+
+macro_rules! long_chain {
+    ($model:ident, $version:tt, $previous:ident, $previous_version:tt, $shortcut:ident, $shortcut_version:tt) => {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        #[native_model(id = 2, version = $version, from = $previous, upgrade_from = [$shortcut])]
+        struct $model {
+            path: Vec<u32>,
+        }
+
+        upgrade!($previous, $previous_version, $model);
+        upgrade!($shortcut, $shortcut_version, $model);
+        upgrade!($model, $version, $previous);
+    };
+}
+
+long_chain!(Bar3, 3, Bar2, 2, Bar1, 1);
+long_chain!(Bar4, 4, Bar3, 3, Bar2, 2);
+long_chain!(Bar5, 5, Bar4, 4, Bar3, 3);
+long_chain!(Bar6, 6, Bar5, 5, Bar4, 4);
+long_chain!(Bar7, 7, Bar6, 6, Bar5, 5);
+long_chain!(Bar8, 8, Bar7, 7, Bar6, 6);
+long_chain!(Bar9, 9, Bar8, 8, Bar7, 7);
+long_chain!(Bar10, 10, Bar9, 9, Bar8, 8);
+long_chain!(Bar11, 11, Bar10, 10, Bar9, 9);
+long_chain!(Bar12, 12, Bar11, 11, Bar10, 10);
+long_chain!(Bar13, 13, Bar12, 12, Bar11, 11);
+long_chain!(Bar14, 14, Bar13, 13, Bar12, 12);
+long_chain!(Bar15, 15, Bar14, 14, Bar13, 13);
+long_chain!(Bar16, 16, Bar15, 15, Bar14, 14);
+long_chain!(Bar17, 17, Bar16, 16, Bar15, 15);
+long_chain!(Bar18, 18, Bar17, 17, Bar16, 16);
+long_chain!(Bar19, 19, Bar18, 18, Bar17, 17);
+long_chain!(Bar20, 20, Bar19, 19, Bar18, 18);
+long_chain!(Bar21, 21, Bar20, 20, Bar19, 19);
+long_chain!(Bar22, 22, Bar21, 21, Bar20, 20);
+long_chain!(Bar23, 23, Bar22, 22, Bar21, 21);
+long_chain!(Bar24, 24, Bar23, 23, Bar22, 22);
+long_chain!(Bar25, 25, Bar24, 24, Bar23, 23);
+long_chain!(Bar26, 26, Bar25, 25, Bar24, 24);
+long_chain!(Bar27, 27, Bar26, 26, Bar25, 25);
+long_chain!(Bar28, 28, Bar27, 27, Bar26, 26);
+long_chain!(Bar29, 29, Bar28, 28, Bar27, 27);
+long_chain!(Bar30, 30, Bar29, 29, Bar28, 28);
+long_chain!(Bar31, 31, Bar30, 30, Bar29, 29);
+long_chain!(Bar32, 32, Bar31, 31, Bar30, 30);
+long_chain!(Bar33, 33, Bar32, 32, Bar31, 31);
+long_chain!(Bar34, 34, Bar33, 33, Bar32, 32);
+long_chain!(Bar35, 35, Bar34, 34, Bar33, 33);
+long_chain!(Bar36, 36, Bar35, 35, Bar34, 34);
+long_chain!(Bar37, 37, Bar36, 36, Bar35, 35);
+long_chain!(Bar38, 38, Bar37, 37, Bar36, 36);
+long_chain!(Bar39, 39, Bar38, 38, Bar37, 37);
+long_chain!(Bar40, 40, Bar39, 39, Bar38, 38);
+
+#[test]
+fn upgrade_from_long_chain() {
+    use native_model::Model;
+
+    assert_eq!(Bar40::native_model_upgrade_cost(1), Some(20));
+    assert_eq!(Bar40::native_model_upgrade_cost(2), Some(19));
+
+    let bar1_packed = native_model::encode(&Bar1 { path: vec![] }).unwrap();
+    let (bar40, _) = native_model::decode::<Bar40>(bar1_packed).unwrap();
+    assert_eq!(
+        bar40,
+        Bar40 {
+            path: (1..40).step_by(2).collect()
+        }
+    );
+}
+
+// `Baz2` needs a context, the shortcut through it is only taken by the
+// decodes with a context.
+
+struct Context;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 1)]
+struct Baz1 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 2, upgrade_with = (Baz1, Context))]
+struct Baz2 {
+    path: Vec<u32>,
+}
+
+impl native_model::UpgradeWith<Baz1, Context> for Baz2 {
+    type Error = std::convert::Infallible;
+
+    fn upgrade_with(from: Baz1, _: &Context) -> Result<Self, Self::Error> {
+        Ok(from.into())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 3, from = Baz1)]
+struct Baz3 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 4, from = Baz3)]
+struct Baz4 {
+    path: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 3, version = 5, from = Baz4, upgrade_from = [Baz2])]
+struct Baz5 {
+    path: Vec<u32>,
+}
+
+upgrade!(Baz1, 1, Baz2);
+upgrade!(Baz1, 1, Baz3);
+upgrade!(Baz3, 3, Baz4);
+upgrade!(Baz4, 4, Baz5);
+upgrade!(Baz2, 2, Baz5);
+upgrade!(Baz3, 3, Baz1);
+upgrade!(Baz4, 4, Baz3);
+upgrade!(Baz5, 5, Baz4);
+
+#[test]
+fn upgrade_from_without_context() {
+    use native_model::Model;
+
+    assert_eq!(Baz5::native_model_upgrade_cost(1), Some(2));
+
+    let baz1_packed = native_model::encode(&Baz1 { path: vec![] }).unwrap();
+    let (baz5, _) = native_model::decode::<Baz5>(baz1_packed.clone()).unwrap();
+    assert_eq!(
+        baz5,
+        Baz5 {
+            path: vec![1, 3, 4]
+        }
+    );
+
+    let (baz5, _) = native_model::decode_with_context::<Baz5, _>(&baz1_packed, &Context).unwrap();
+    assert_eq!(baz5, Baz5 { path: vec![1, 2] });
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, UpgradeReport};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct Foo1 {
+    x: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, from = Foo1)]
+struct Foo2 {
+    x: i32,
+    name: String,
+}
+
+impl From<Foo1> for Foo2 {
+    fn from(foo1: Foo1) -> Self {
+        Foo2 {
+            x: foo1.x,
+            name: String::new(),
+        }
+    }
+}
+
+impl From<Foo2> for Foo1 {
+    fn from(foo2: Foo2) -> Self {
+        Foo1 { x: foo2.x }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3, try_from = (Foo2, anyhow::Error))]
+struct Foo3 {
+    x: u32,
+    name: String,
+}
+
+impl TryFrom<Foo2> for Foo3 {
+    type Error = anyhow::Error;
+
+    fn try_from(foo2: Foo2) -> Result<Self, Self::Error> {
+        Ok(Foo3 {
+            x: foo2.x.try_into()?,
+            name: foo2.name,
+        })
+    }
+}
+
+impl TryFrom<Foo3> for Foo2 {
+    type Error = anyhow::Error;
+
+    fn try_from(foo3: Foo3) -> Result<Self, Self::Error> {
+        Ok(Foo2 {
+            x: foo3.x.try_into()?,
+            name: foo3.name,
+        })
+    }
+}
+
+#[test]
+fn upgrade_report_not_upgraded() {
+    let foo3 = Foo3 {
+        x: 3,
+        name: "foo".to_string(),
+    };
+    let foo3_packed = native_model::encode(&foo3).unwrap();
+    let (foo3_decoded, report) = native_model::decode_with_report::<Foo3>(&foo3_packed).unwrap();
+    assert_eq!(foo3, foo3_decoded);
+    assert_eq!(report, UpgradeReport::new(3, 3));
+    assert!(!report.is_upgraded());
+    assert!(!report.is_fallible());
+}
+
+#[test]
+fn upgrade_report_from() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (foo2_decoded, report) = native_model::decode_with_report::<Foo2>(&foo1_packed).unwrap();
+    assert_eq!(
+        Foo2 {
+            x: 1,
+            name: String::new()
+        },
+        foo2_decoded
+    );
+    assert_eq!(report.source_version, 1);
+    assert_eq!(report.target_version, 2);
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!(report.steps[0].from_version, 1);
+    assert_eq!(report.steps[0].to_version, 2);
+    assert!(report.steps[0].from_type.ends_with("Foo1"));
+    assert!(report.steps[0].to_type.ends_with("Foo2"));
+    assert!(report.is_upgraded());
+    assert!(!report.is_fallible());
+}
+
+#[test]
+fn upgrade_report_try_from() {
+    let foo1_packed = native_model::encode(&Foo1 { x: 1 }).unwrap();
+    let (foo3_decoded, report) = native_model::decode_with_report::<Foo3>(&foo1_packed).unwrap();
+    assert_eq!(
+        Foo3 {
+            x: 1,
+            name: String::new()
+        },
+        foo3_decoded
+    );
+    assert_eq!((report.source_version, report.target_version), (1, 3));
+    let versions = report
+        .steps
+        .iter()
+        .map(|step| (step.from_version, step.to_version, step.fallible))
+        .collect::<Vec<_>>();
+    assert_eq!(versions, vec![(1, 2, false), (2, 3, true)]);
+    assert!(report.is_fallible());
+
+    let foo2_packed = native_model::encode(&Foo2 {
+        x: 2,
+        name: String::new(),
+    })
+    .unwrap();
+    let (_, report) = native_model::decode_with_report::<Foo3>(&foo2_packed).unwrap();
+    assert_eq!(report.steps.len(), 1);
+    assert!(report.steps[0].from_type.ends_with("Foo2"));
+    assert!(report.steps[0].to_type.ends_with("Foo3"));
+}
+
+#[test]
+fn upgrade_report_errors() {
+    let foo1_packed = native_model::encode(&Foo1 { x: -1 }).unwrap();
+    let result = native_model::decode_with_report::<Foo3>(&foo1_packed);
+    assert!(matches!(result, Err(native_model::Error::UpgradeError(_))));
+
+    let foo3_packed = native_model::encode(&Foo3 {
+        x: 3,
+        name: String::new(),
+    })
+    .unwrap();
+    let result = native_model::decode_with_report::<Foo2>(&foo3_packed);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::UpgradeNotSupported { from: 3, to: 2 })
+    ));
+
+    let result = native_model::decode_with_report::<Foo2>(&foo3_packed[..4]);
+    assert!(matches!(result, Err(native_model::Error::InvalidHeader)));
+}
@@ -0,0 +1,178 @@
+#![cfg(feature = "bincode_1_3")]
+
+use native_model::{native_model, UpgradeWith};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// State the upgrades need, not present in the old records.
+struct Context {
+    default_tenant: u32,
+    countries: HashMap<String, u32>,
+}
+
+fn context() -> Context {
+    Context {
+        default_tenant: 7,
+        countries: HashMap::from([("fr".to_string(), 33), ("us".to_string(), 1)]),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 1)]
+struct User1 {
+    name: String,
+    country: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 2, upgrade_with = (User1, Context))]
+struct User2 {
+    name: String,
+    country: String,
+    tenant: u32,
+}
+
+impl UpgradeWith<User1, Context> for User2 {
+    type Error = std::convert::Infallible;
+
+    fn upgrade_with(user: User1, context: &Context) -> Result<Self, Self::Error> {
+        Ok(User2 {
+            name: user.name,
+            country: user.country,
+            tenant: context.default_tenant,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 3, upgrade_with = (User2, Context))]
+struct User3 {
+    name: String,
+    country_code: u32,
+    tenant: u32,
+}
+
+impl UpgradeWith<User2, Context> for User3 {
+    type Error = anyhow::Error;
+
+    fn upgrade_with(user: User2, context: &Context) -> Result<Self, Self::Error> {
+        let country_code = context
+            .countries
+            .get(&user.country)
+            .ok_or_else(|| anyhow::anyhow!("unknown country {}", user.country))?;
+        Ok(User3 {
+            name: user.name,
+            country_code: *country_code,
+            tenant: user.tenant,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[native_model(id = 1, version = 4, from = User3)]
+struct User4 {
+    name: String,
+    country_code: u32,
+    tenant: u32,
+    admin: bool,
+}
+
+impl From<User3> for User4 {
+    fn from(user: User3) -> Self {
+        User4 {
+            name: user.name,
+            country_code: user.country_code,
+            tenant: user.tenant,
+            admin: false,
+        }
+    }
+}
+
+impl From<User4> for User3 {
+    fn from(user: User4) -> Self {
+        User3 {
+            name: user.name,
+            country_code: user.country_code,
+            tenant: user.tenant,
+        }
+    }
+}
+
+fn user1(country: &str) -> Vec<u8> {
+    native_model::encode(&User1 {
+        name: "alice".to_string(),
+        country: country.to_string(),
+    })
+    .unwrap()
+}
+
+#[test]
+fn decode_with_context() {
+    let (user, version) =
+        native_model::decode_with_context::<User4, _>(&user1("fr"), &context()).unwrap();
+    assert_eq!(
+        user,
+        User4 {
+            name: "alice".to_string(),
+            country_code: 33,
+            tenant: 7,
+            admin: false,
+        }
+    );
+    assert_eq!(version, 1);
+
+    let (user, version) =
+        native_model::decode_with_context::<User2, _>(&user1("us"), &context()).unwrap();
+    assert_eq!(user.tenant, 7);
+    assert_eq!(version, 1);
+}
+
+#[test]
+fn decode_with_context_not_needed() {
+    let user3 = User3 {
+        name: "bob".to_string(),
+        country_code: 1,
+        tenant: 2,
+    };
+    let user3_packed = native_model::encode(&user3).unwrap();
+    let (user, _) = native_model::decode::<User4>(user3_packed.clone()).unwrap();
+    assert_eq!(user.tenant, 2);
+    let (user, _) = native_model::decode_with_context::<User4, _>(&user3_packed, &()).unwrap();
+    assert_eq!(user.tenant, 2);
+}
+
+#[test]
+fn decode_with_context_missing() {
+    let result = native_model::decode::<User4>(user1("fr"));
+    assert!(matches!(
+        result,
+        Err(native_model::Error::MissingUpgradeContext { context }) if context.ends_with("Context")
+    ));
+
+    let result = native_model::decode_from_reader::<User4>(&user1("fr")[..]);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::MissingUpgradeContext { .. })
+    ));
+
+    let result = native_model::decode_with_report::<User4>(&user1("fr"));
+    assert!(matches!(
+        result,
+        Err(native_model::Error::MissingUpgradeContext { .. })
+    ));
+
+    // Context of another type.
+    let result = native_model::decode_with_context::<User4, _>(&user1("fr"), &7u32);
+    assert!(matches!(
+        result,
+        Err(native_model::Error::MissingUpgradeContext { .. })
+    ));
+}
+
+#[test]
+fn decode_with_context_error() {
+    let result = native_model::decode_with_context::<User4, _>(&user1("de"), &context());
+    assert!(
+        matches!(&result, Err(native_model::Error::UpgradeError(e)) if e.msg == "unknown country de")
+    );
+}